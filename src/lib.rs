@@ -22,7 +22,8 @@ pub mod plugins;
 
 pub use crate::mdparser::main::MarkdownIt;
 use crate::plugin_config::{
-    CitationExtensionPlugin, DisplayMathExtensionPlugin, InlineMathExtensionPlugin,
+    AutolinkUrlExtensionPlugin, CitationExtensionPlugin, DisplayMathExtensionPlugin,
+    InlineMathExtensionPlugin,
 };
 use crate::plugins::cmark::COMMONMARK_PLUGIN_NAMES;
 use crate::plugins::extra::gh_flavored_md::GITHUB_PLUGIN_NAMES;
@@ -30,9 +31,11 @@ use crate::plugins::kagi_plugins::citation::CitationQM;
 use crate::plugins::kagi_plugins::inkjet::warmup;
 use crate::plugins::kagi_plugins::KAGI_PLUGIN_NAMES;
 use crate::plugins::kagi_plugins::*;
+pub use mdparser::markdown_renderer::{to_commonmark, CommonmarkOptions};
 pub use mdparser::node::{Node, NodeValue};
 pub use mdparser::preprocess::preprocess;
 pub use mdparser::renderer::Renderer;
+pub use mdparser::sexpr::to_sexpr;
 use plugin_config::ImageExtensionPlugin;
 use plugin_config::InkjetPlugin;
 mod plugin_config;
@@ -45,21 +48,37 @@ use plugin_config::Plugin;
 //
 //
 use pyo3::{exceptions::PyRuntimeError, prelude::*};
+mod line_index;
 mod nodes;
 
-use once_cell::sync::Lazy;
-use std::{panic, panic::AssertUnwindSafe, panic::PanicHookInfo, sync::Mutex};
+use line_index::LineIndex;
 
-// NOTE(Rehan): storage for the most recent panic message
-// need to be mutex to be global variable that can be written to on runtime
-// even though multiple threads not expected
-static LAST_PANIC: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+use std::cell::RefCell;
+use std::{panic, panic::AssertUnwindSafe, panic::PanicHookInfo};
+
+// NOTE(Rehan): a panic captured during `render`/`render_sexpr`/`tree` always
+// happens on the calling thread inside `catch_unwind`, so storing it
+// thread-local (rather than in one global `Mutex`) means two threads calling
+// in concurrently can never steal each other's panic message.
+thread_local! {
+    static LAST_PANIC: RefCell<Option<PanicRecord>> = const { RefCell::new(None) };
+}
+
+/// A panic captured from this thread's most recent `catch_unwind`'d
+/// parse/render call, typed instead of a flat string so callers can tell a
+/// genuine internal bug apart from a recoverable input problem.
+#[derive(Debug, Clone)]
+struct PanicRecord {
+    message: String,
+    file: Option<String>,
+    line: Option<u32>,
+}
 
 /// throw in our custom panic hook to silence MDRS panics and store the message instead
 pub fn init_panic_hook() {
     std::panic::set_hook(Box::new(|info: &PanicHookInfo| {
         // NOTE(Rehan): payload often &str or String, but can be other stuff
-        let mut msg = match info.payload().downcast_ref::<&str>() {
+        let message = match info.payload().downcast_ref::<&str>() {
             Some(s) => (*s).to_string(),
             None => match info.payload().downcast_ref::<String>() {
                 Some(s) => s.clone(),
@@ -68,14 +87,52 @@ pub fn init_panic_hook() {
         };
 
         // NOTE(Rehan): location part of panic - part that points out line number of file and whatnot
-        if let Some(location) = info.location() {
-            msg.push_str(&format!(" at {}:{}", location.file(), location.line()));
-        }
+        let (file, line) = match info.location() {
+            Some(location) => (Some(location.file().to_string()), Some(location.line())),
+            None => (None, None),
+        };
 
-        *(LAST_PANIC.lock().unwrap()) = Some(msg);
+        LAST_PANIC.with(|cell| {
+            *cell.borrow_mut() = Some(PanicRecord { message, file, line });
+        });
     }));
 }
 
+/// Richer Python exception for a parse/render that hit an internal panic,
+/// exposing `.message`, `.file`, and `.line` instead of a flat string so
+/// callers can distinguish a genuine internal bug from recoverable input
+/// problems.
+#[pyclass(extends = PyRuntimeError)]
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    #[pyo3(get)]
+    pub message: String,
+    #[pyo3(get)]
+    pub file: Option<String>,
+    #[pyo3(get)]
+    pub line: Option<u32>,
+}
+
+#[pymethods]
+impl ParseError {
+    #[new]
+    fn new(message: String, file: Option<String>, line: Option<u32>) -> Self {
+        ParseError { message, file, line }
+    }
+}
+
+/// Take this thread's captured panic (if any) and turn it into a
+/// [`ParseError`], clearing it so a later unrelated panic doesn't get
+/// attributed to the next call on this thread.
+fn panic_to_pyerr() -> PyErr {
+    let record = LAST_PANIC.with(|cell| cell.borrow_mut().take());
+    let (message, file, line) = match record {
+        Some(record) => (record.message, record.file, record.line),
+        None => ("Rust panic occurred".to_string(), None, None),
+    };
+    PyErr::new::<ParseError, _>((message, file, line))
+}
+
 #[derive(FromPyObject)]
 enum AnyPlugin<'py> {
     #[pyo3(transparent)]
@@ -96,10 +153,109 @@ enum AnyPlugin<'py> {
     #[pyo3(transparent)]
     Inkjet(PyRef<'py, InkjetPlugin>),
 
+    #[pyo3(transparent)]
+    AutolinkUrl(PyRef<'py, AutolinkUrlExtensionPlugin>),
+
     #[pyo3(transparent)]
     Base(PyRef<'py, Plugin>),
 }
 
+/// Which plugin family a [`PluginEntry`] belongs to, for grouping in
+/// introspection. Doesn't gate what `new` enables for a given `config` —
+/// the `kagi`/`commonmark`/`gfm` presets keep calling their own bulk
+/// `add` functions, since those do extra non-plugin wiring (e.g. GFM's
+/// raw-HTML tag filter) that isn't expressible as a single registry entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginCategory {
+    Commonmark,
+    Gfm,
+    Kagi,
+}
+
+/// One entry in [`PLUGIN_REGISTRY`]: `add` wires this plugin, with its
+/// default config where it takes one, into a [`MarkdownIt`] parser.
+struct PluginEntry {
+    name: &'static str,
+    category: PluginCategory,
+    /// Whether [`preprocess`] has a preprocessing step gated on this
+    /// plugin's name (see its `processors` list).
+    needs_preprocessing: bool,
+    add: fn(&mut MarkdownIt),
+}
+
+fn add_autolink_url(md: &mut MarkdownIt) {
+    crate::plugins::kagi_plugins::autolink_url::add(md, AutolinkUrlExtensionPlugin::default());
+}
+fn add_citation(md: &mut MarkdownIt) {
+    crate::plugins::kagi_plugins::citation::add(md, CitationExtensionPlugin::default());
+}
+fn add_inkjet(md: &mut MarkdownIt) {
+    crate::plugins::kagi_plugins::inkjet::add(md, InkjetPlugin::default());
+}
+fn add_kagi_link(md: &mut MarkdownIt) {
+    crate::plugins::kagi_plugins::link::add(md, LinkExtensionPlugin::default());
+}
+fn add_kagi_image(md: &mut MarkdownIt) {
+    crate::plugins::kagi_plugins::image::add(md, ImageExtensionPlugin::default());
+}
+fn add_inline_math(md: &mut MarkdownIt) {
+    crate::plugins::kagi_plugins::math_inline::add(md, InlineMathExtensionPlugin::default());
+}
+fn add_display_math(md: &mut MarkdownIt) {
+    crate::plugins::kagi_plugins::math_display::add(md, DisplayMathExtensionPlugin::default());
+}
+
+/// Single source of truth for every individually-enableable plugin name:
+/// `_enable_str`, `list_plugins`, and the rebuild that backs `disable`/
+/// `disable_many` all dispatch off this instead of each keeping their own
+/// hand-written list, which is what let `list_plugins` go stale and drift
+/// out of sync with `kagi_plugins::mod.rs` in the first place.
+const PLUGIN_REGISTRY: &[PluginEntry] = &[
+    PluginEntry { name: "nl2br", category: PluginCategory::Kagi, needs_preprocessing: false, add: crate::plugins::kagi_plugins::nl2br::add },
+    PluginEntry { name: "autolink_url", category: PluginCategory::Kagi, needs_preprocessing: false, add: add_autolink_url },
+    PluginEntry { name: "citation", category: PluginCategory::Kagi, needs_preprocessing: true, add: add_citation },
+    PluginEntry { name: "blockquote", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::block::blockquote::add },
+    PluginEntry { name: "code", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::block::code::add },
+    PluginEntry { name: "inkjet", category: PluginCategory::Kagi, needs_preprocessing: false, add: add_inkjet },
+    PluginEntry { name: "fence", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::block::fence::add },
+    PluginEntry { name: "heading", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::block::heading::add },
+    PluginEntry { name: "hr", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::block::hr::add },
+    PluginEntry { name: "lheading", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::block::lheading::add },
+    PluginEntry { name: "list", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::block::list::add },
+    PluginEntry { name: "paragraph", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::block::paragraph::add },
+    PluginEntry { name: "reference", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::block::reference::add },
+    PluginEntry { name: "autolink", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::inline::autolink::add },
+    PluginEntry { name: "kagi_link", category: PluginCategory::Kagi, needs_preprocessing: false, add: add_kagi_link },
+    PluginEntry { name: "kagi_image", category: PluginCategory::Kagi, needs_preprocessing: false, add: add_kagi_image },
+    PluginEntry { name: "kagi_contact_info", category: PluginCategory::Kagi, needs_preprocessing: true, add: crate::plugins::kagi_plugins::contact_info::add },
+    PluginEntry { name: "backticks", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::inline::backticks::add },
+    PluginEntry { name: "emphasis", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::inline::emphasis::add },
+    PluginEntry { name: "entity", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::inline::entity::add },
+    PluginEntry { name: "escape", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::inline::escape::add },
+    PluginEntry { name: "image", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::inline::image::add },
+    PluginEntry { name: "link", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::inline::link::add },
+    PluginEntry { name: "newline", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::cmark::inline::newline::add },
+    PluginEntry { name: "html_block", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::html::html_block::add },
+    PluginEntry { name: "html_inline", category: PluginCategory::Commonmark, needs_preprocessing: false, add: crate::plugins::html::html_inline::add },
+    PluginEntry { name: "linkify", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::extra::linkify::add },
+    PluginEntry { name: "replacements", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::extra::typographer::add },
+    PluginEntry { name: "smartquotes", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::extra::smartquotes::add },
+    PluginEntry { name: "sourcepos", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::sourcepos::add },
+    PluginEntry { name: "strikethrough", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::extra::strikethrough::add },
+    PluginEntry { name: "table", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::extra::tables::add },
+    PluginEntry { name: "front_matter", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::extra::front_matter::add },
+    PluginEntry { name: "tasklist", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::extra::tasklist::add },
+    PluginEntry { name: "footnote", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::footnote::add },
+    PluginEntry { name: "heading_anchors", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::extra::heading_anchors::add },
+    PluginEntry { name: "autolink_ext", category: PluginCategory::Gfm, needs_preprocessing: false, add: crate::plugins::autolinks::add },
+    PluginEntry { name: "inline_math", category: PluginCategory::Kagi, needs_preprocessing: false, add: add_inline_math },
+    PluginEntry { name: "display_math", category: PluginCategory::Kagi, needs_preprocessing: false, add: add_display_math },
+];
+
+fn registry_entry(name: &str) -> Option<&'static PluginEntry> {
+    PLUGIN_REGISTRY.iter().find(|entry| entry.name == name)
+}
+
 /// Main parser class
 #[pyclass]
 #[derive(Debug)]
@@ -110,149 +266,16 @@ pub struct MDParser {
 
 impl MDParser {
     pub fn _enable_str(&mut self, name: &str) -> Result<(), PyErr> {
-        match name {
-            "nl2br" => {
-                crate::plugins::kagi_plugins::nl2br::add(&mut self.parser);
-            }
-            "citation" => {
-                crate::plugins::kagi_plugins::citation::add(
-                    &mut self.parser,
-                    CitationExtensionPlugin::default(),
-                );
-            }
-            "blockquote" => {
-                crate::plugins::cmark::block::blockquote::add(&mut self.parser);
-            }
-            "code" => {
-                crate::plugins::cmark::block::code::add(&mut self.parser);
-            }
-            "inkjet" => {
-                crate::plugins::kagi_plugins::inkjet::add(
-                    &mut self.parser,
-                    InkjetPlugin::default(),
-                );
-            }
-            "fence" => {
-                crate::plugins::cmark::block::fence::add(&mut self.parser);
-            }
-            "heading" => {
-                crate::plugins::cmark::block::heading::add(&mut self.parser);
-            }
-            "hr" => {
-                crate::plugins::cmark::block::hr::add(&mut self.parser);
-            }
-            "lheading" => {
-                crate::plugins::cmark::block::lheading::add(&mut self.parser);
-            }
-            "list" => {
-                crate::plugins::cmark::block::list::add(&mut self.parser);
-            }
-            "paragraph" => {
-                crate::plugins::cmark::block::paragraph::add(&mut self.parser);
-            }
-            "reference" => {
-                crate::plugins::cmark::block::reference::add(&mut self.parser);
-            }
-            "autolink" => {
-                crate::plugins::cmark::inline::autolink::add(&mut self.parser);
-            }
-            "kagi_link" => {
-                crate::plugins::kagi_plugins::link::add(
-                    &mut self.parser,
-                    LinkExtensionPlugin::default(),
-                );
-            }
-            "kagi_image" => {
-                crate::plugins::kagi_plugins::image::add(
-                    &mut self.parser,
-                    ImageExtensionPlugin::default(),
-                );
-            }
-            "kagi_contact_info" => {
-                crate::plugins::kagi_plugins::contact_info::add(&mut self.parser);
-            }
-            "backticks" => {
-                crate::plugins::cmark::inline::backticks::add(&mut self.parser);
-            }
-            "emphasis" => {
-                crate::plugins::cmark::inline::emphasis::add(&mut self.parser);
-            }
-            "entity" => {
-                crate::plugins::cmark::inline::entity::add(&mut self.parser);
-            }
-            "escape" => {
-                crate::plugins::cmark::inline::escape::add(&mut self.parser);
-            }
-            "image" => {
-                crate::plugins::cmark::inline::image::add(&mut self.parser);
-            }
-            "link" => {
-                crate::plugins::cmark::inline::link::add(&mut self.parser);
-            }
-            "newline" => {
-                crate::plugins::cmark::inline::newline::add(&mut self.parser);
-            }
-            "html_block" => {
-                crate::plugins::html::html_block::add(&mut self.parser);
-            }
-            "html_inline" => {
-                crate::plugins::html::html_inline::add(&mut self.parser);
-            }
-            "linkify" => {
-                crate::plugins::extra::linkify::add(&mut self.parser);
-            }
-            "replacements" => {
-                crate::plugins::extra::typographer::add(&mut self.parser);
-            }
-            "smartquotes" => {
-                crate::plugins::extra::smartquotes::add(&mut self.parser);
-            }
-            "sourcepos" => {
-                crate::plugins::sourcepos::add(&mut self.parser);
-            }
-            "strikethrough" => {
-                crate::plugins::extra::strikethrough::add(&mut self.parser);
-            }
-            "table" => {
-                crate::plugins::extra::tables::add(&mut self.parser);
-            }
-            "front_matter" => {
-                crate::plugins::extra::front_matter::add(&mut self.parser);
-            }
-            "tasklist" => {
-                crate::plugins::extra::tasklist::add(&mut self.parser);
-            }
-            "footnote" => {
-                crate::plugins::footnote::add(&mut self.parser);
-            }
-            "heading_anchors" => {
-                crate::plugins::extra::heading_anchors::add(&mut self.parser);
-            }
-            "autolink_ext" => {
-                crate::plugins::autolinks::add(&mut self.parser);
-            }
-            "inline_math" => {
-                crate::plugins::kagi_plugins::math_inline::add(
-                    &mut self.parser,
-                    InlineMathExtensionPlugin::default(),
-                );
-            }
-            "display_math" => {
-                crate::plugins::kagi_plugins::math_display::add(
-                    &mut self.parser,
-                    DisplayMathExtensionPlugin::default(),
-                );
-            }
-            _ => {
-                return {
-                    Err(pyo3::exceptions::PyValueError::new_err(format!(
-                        "Unknown plugin: {}",
-                        name
-                    )))
-                }
-            }
+        match registry_entry(name) {
+            Some(entry) => {
+                (entry.add)(&mut self.parser);
+                Ok(())
+            }
+            None => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown plugin: {}",
+                name
+            ))),
         }
-        Ok(())
     }
 
     fn _enable(&mut self, py: Python, plugin: Py<Plugin>) -> Result<(), PyErr> {
@@ -263,6 +286,7 @@ impl MDParser {
             AnyPlugin::InlineMath(p) => math_inline::add(&mut self.parser, *p),
             AnyPlugin::DisplayMath(p) => math_display::add(&mut self.parser, *p),
             AnyPlugin::Inkjet(p) => inkjet::add(&mut self.parser, *p),
+            AnyPlugin::AutolinkUrl(p) => autolink_url::add(&mut self.parser, *p),
             AnyPlugin::Base(p) => self._enable_str(&p.name)?,
         }
         self.enabled_plugin_names
@@ -270,6 +294,28 @@ impl MDParser {
 
         Ok(())
     }
+
+    /// Rebuild `self.parser` from scratch, replaying the registry's `add`
+    /// for every name still in `enabled_plugin_names`. This is the only
+    /// way to "turn off" a plugin, since the underlying engine has no way
+    /// to remove a rule once it's been added to a parser.
+    ///
+    /// Names that came from the `kagi`/`commonmark`/`gfm` presets (rather
+    /// than individual `enable` calls) still rebuild correctly as long as
+    /// each is registered — but any extra wiring those presets do beyond
+    /// calling each named plugin's `add` (GFM's raw-HTML tag filter,
+    /// kagi's diagnostics collector, ...) is not replayed, since it isn't
+    /// attached to a plugin name. Compose from `"zero"` with
+    /// `enable`/`disable` if you need that to survive.
+    fn rebuild(&mut self) {
+        let mut parser = MarkdownIt::new();
+        for name in &self.enabled_plugin_names {
+            if let Some(entry) = registry_entry(name) {
+                (entry.add)(&mut parser);
+            }
+        }
+        self.parser = parser;
+    }
 }
 
 #[pymethods]
@@ -333,43 +379,47 @@ impl MDParser {
         crate::plugins::cmark::block::fence::set_lang_prefix(&mut self.parser, "");
     }
 
+    /// Every individually-enableable plugin name, read straight from the
+    /// registry so this can never again omit a plugin family the way the
+    /// old hardcoded list omitted every kagi plugin.
     #[staticmethod]
     fn list_plugins() -> Vec<String> {
-        vec![
-            "blockquote",
-            "code",
-            "fence",
-            "heading",
-            "hr",
-            "lheading",
-            "list",
-            "paragraph",
-            "reference",
-            "autolink",
-            "backticks",
-            "emphasis",
-            "entity",
-            "escape",
-            "image",
-            "link",
-            "newline",
-            "html_block",
-            "html_inline",
-            "linkify",
-            "replacements",
-            "smartquotes",
-            "sourcepos",
-            "strikethrough",
-            "table",
-            "front_matter",
-            "tasklist",
-            "footnote",
-            "heading_anchors",
-            "autolink_ext",
-        ]
-        .iter()
-        .map(|s| s.to_string())
-        .collect()
+        PLUGIN_REGISTRY
+            .iter()
+            .map(|entry| entry.name.to_string())
+            .collect()
+    }
+
+    /// Plugin names in one category: `"commonmark"`, `"gfm"`, or `"kagi"`.
+    #[staticmethod]
+    fn list_plugins_by_category(category: &str) -> PyResult<Vec<String>> {
+        let category = match category {
+            "commonmark" => PluginCategory::Commonmark,
+            "gfm" => PluginCategory::Gfm,
+            "kagi" => PluginCategory::Kagi,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown plugin category: {}",
+                    category
+                )))
+            }
+        };
+        Ok(PLUGIN_REGISTRY
+            .iter()
+            .filter(|entry| entry.category == category)
+            .map(|entry| entry.name.to_string())
+            .collect())
+    }
+
+    /// Plugin names whose preprocessing step only runs when they're
+    /// enabled (see [`preprocess`]).
+    #[staticmethod]
+    fn plugins_needing_preprocessing() -> Vec<String> {
+        PLUGIN_REGISTRY
+            .iter()
+            .filter(|entry| entry.needs_preprocessing)
+            .map(|entry| entry.name.to_string())
+            .collect()
     }
 
     /// Enable a plugin
@@ -386,6 +436,44 @@ impl MDParser {
         Ok(slf)
     }
 
+    /// Disable a previously-enabled plugin by name. Implemented by
+    /// rebuilding the parser from scratch from the remaining enabled
+    /// names, since the underlying engine has no way to remove a rule
+    /// once added — so any extra wiring a preset does beyond calling each
+    /// named plugin's `add` won't survive a `disable` call.
+    fn disable(&mut self, name: &str) -> PyResult<()> {
+        if registry_entry(name).is_none() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown plugin: {}",
+                name
+            )));
+        }
+        self.enabled_plugin_names.retain(|enabled| enabled != name);
+        self.rebuild();
+        Ok(())
+    }
+
+    /// Disable multiple plugins; see [`MDParser::disable`].
+    fn disable_many(&mut self, names: Vec<String>) -> PyResult<()> {
+        for name in &names {
+            if registry_entry(name).is_none() {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown plugin: {}",
+                    name
+                )));
+            }
+        }
+        self.enabled_plugin_names
+            .retain(|enabled| !names.contains(enabled));
+        self.rebuild();
+        Ok(())
+    }
+
+    /// The plugin names currently enabled on this parser.
+    fn active_plugins(&self) -> Vec<String> {
+        self.enabled_plugin_names.clone()
+    }
+
     /// Render markdown string into HTML.
     /// If `xhtml` is true, then self-closing tags will include a slash, e.g. `<br />`.
     #[pyo3(signature = (src, *, xhtml=true))]
@@ -399,42 +487,93 @@ impl MDParser {
             }
         }));
 
-        match result {
-            Ok(html) => Ok(html),
-            Err(_) => {
-                // unwrap ok here, can only be error if another thread doesn't let go of mutex
-                // but we don't expect that, one panic and we send the error up and stop
-                let lock_result = LAST_PANIC.lock();
-                let msg = match lock_result {
-                    Err(_) => "mutex lock failed".to_owned(),
-                    Ok(mut lock) => lock
-                        .take()
-                        .unwrap_or_else(|| "Rust panic occurred".to_owned()),
-                };
-
-                Err(PyRuntimeError::new_err(msg))
-            }
-        }
+        result.map_err(|_| panic_to_pyerr())
     }
-    /// Create a syntax tree from the markdown string.
-    fn tree(&self, py: Python, src: &str) -> nodes::Node {
-        let ast = self.parser.parse(src);
 
-        fn walk_recursive(py: Python, node: &crate::Node, py_node: &mut nodes::Node) {
+    /// Render markdown string into HTML, alongside every
+    /// [`Diagnostic`](crate::plugins::diagnostics::Diagnostic) (a broken
+    /// citation, a dangling footnote reference, ...) collected while
+    /// parsing it, for callers linting user-authored markdown that want to
+    /// report exactly what failed to resolve instead of the silent
+    /// fallback `render` uses.
+    pub fn render_with_diagnostics(
+        &self,
+        src: &str,
+    ) -> PyResult<(String, Vec<crate::plugins::diagnostics::Diagnostic>)> {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let preprocessed = preprocess(src, &self.enabled_plugin_names);
+            let ast = self.parser.parse(preprocessed.as_ref());
+            let html = ast.render();
+            let diagnostics = ast
+                .cast::<crate::mdparser::core::Root>()
+                .and_then(|root| root.ext.get::<crate::plugins::diagnostics::Diagnostics>())
+                .map(|d| d.0.clone())
+                .unwrap_or_default();
+            (html, diagnostics)
+        }));
+
+        result.map_err(|_| panic_to_pyerr())
+    }
+
+    /// Render markdown string into a compact S-expression dump of the
+    /// parsed tree, for asserting on tree structure instead of brittle
+    /// HTML strings in plugin tests.
+    pub fn render_sexpr(&self, src: &str) -> PyResult<String> {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let preprocessed = preprocess(src, &self.enabled_plugin_names);
+            let ast = self.parser.parse(preprocessed.as_ref());
+            to_sexpr(&ast)
+        }));
+
+        result.map_err(|_| panic_to_pyerr())
+    }
+
+    /// Create a syntax tree from the markdown string. When sourcepos is
+    /// enabled each returned [`nodes::Node`] carries the `(line, col)` span
+    /// of the markdown it was parsed from, resolved once via a shared
+    /// [`LineIndex`] built from `src`.
+    fn tree(&self, py: Python, src: &str) -> PyResult<nodes::Node> {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.parser.parse(src)));
+        let ast = result.map_err(|_| panic_to_pyerr())?;
+        let index = LineIndex::new(src);
+
+        fn walk_recursive(
+            py: Python,
+            node: &crate::Node,
+            py_node: &mut nodes::Node,
+            src: &str,
+            index: &LineIndex,
+        ) {
             for n in node.children.iter() {
-                let mut py_node_child = nodes::create_node(py, n);
+                let mut py_node_child = nodes::create_node(py, n, src, index);
 
                 stacker::maybe_grow(64 * 1024, 1024 * 1024, || {
-                    walk_recursive(py, n, &mut py_node_child);
+                    walk_recursive(py, n, &mut py_node_child, src, index);
                 });
 
                 py_node.children.push(Py::new(py, py_node_child).unwrap());
             }
         }
 
-        let mut py_node = nodes::create_node(py, &ast);
-        walk_recursive(py, &ast, &mut py_node);
-        py_node
+        let mut py_node = nodes::create_node(py, &ast, src, &index);
+        walk_recursive(py, &ast, &mut py_node, src, &index);
+        Ok(py_node)
+    }
+
+    /// Resolve a byte offset in `src` to a 0-indexed `(line, column)` pair,
+    /// with the column counted in characters so multi-byte UTF-8 content
+    /// maps correctly. Handy for turning a byte offset (e.g. a
+    /// [`CitationQM::md_offset`](crate::plugins::kagi_plugins::citation::CitationQM))
+    /// back into something human-readable for error reporting or editor
+    /// integration.
+    fn line_col(&self, src: &str, offset: usize) -> (usize, usize) {
+        LineIndex::new(src).line_col(src, offset)
+    }
+
+    /// Same as [`MDParser::line_col`], but with the column counted in
+    /// UTF-16 code units instead of characters, for LSP-style consumers.
+    fn line_col_utf16(&self, src: &str, offset: usize) -> (usize, usize) {
+        LineIndex::new(src).line_col_utf16(src, offset)
     }
 
     /// warmup for quickmark
@@ -450,6 +589,7 @@ fn quickmark(m: &Bound<'_, PyModule>) -> PyResult<()> {
     init_panic_hook();
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     m.add_class::<MDParser>()?;
+    m.add_class::<ParseError>()?;
     m.add_class::<nodes::Node>()?;
     m.add_class::<Plugin>()?;
     m.add_class::<ImageExtensionPlugin>()?;
@@ -458,7 +598,10 @@ fn quickmark(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<CitationQM>()?;
     m.add_class::<InlineMathExtensionPlugin>()?;
     m.add_class::<DisplayMathExtensionPlugin>()?;
+    m.add_class::<plugins::kagi_plugins::math::MathMacro>()?;
     m.add_class::<InkjetPlugin>()?;
+    m.add_class::<AutolinkUrlExtensionPlugin>()?;
+    m.add_class::<plugins::diagnostics::Diagnostic>()?;
     // let plugins_module = PyModule::new(py, "plugins")?;
     // plugins_module.add_function(wrap_pyfunction!(plugins::add_heading_anchors, plugins_module)?)?;
     // m.add_submodule(plugins_module)?;
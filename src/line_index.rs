@@ -0,0 +1,58 @@
+//! Byte-offset → (line, column) resolution for source positions.
+//!
+//! Built once per source string by scanning for `\n` and recording the byte
+//! offset of each line start, so resolving an offset back to a human
+//! `(line, column)` — for error reporting or editor integration — is a
+//! binary search instead of a linear re-scan of everything before it.
+
+/// Maps byte offsets into a source string to `(line, column)` pairs, both
+/// 0-indexed. Columns count **characters**, not bytes, so multi-byte UTF-8
+/// content (emoji, accented letters, ...) doesn't throw off the column for
+/// callers that operate on codepoints.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// Byte offset of the start of each line; `line_starts[0]` is always 0.
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(src: &str) -> Self {
+        let mut line_starts = vec![0];
+        for (i, b) in src.bytes().enumerate() {
+            if b == b'\n' {
+                line_starts.push(i + 1);
+            }
+        }
+        Self { line_starts }
+    }
+
+    fn line_for(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line.saturating_sub(1),
+        }
+    }
+
+    /// Resolve a byte `offset` in `src` to a 0-indexed `(line, column)`
+    /// pair, with the column counted in characters. Offsets past the end
+    /// of `src` clamp to its last position.
+    pub fn line_col(&self, src: &str, offset: usize) -> (usize, usize) {
+        let offset = offset.min(src.len());
+        let line = self.line_for(offset);
+        let col = src[self.line_starts[line]..offset].chars().count();
+        (line, col)
+    }
+
+    /// Same as [`LineIndex::line_col`], but with the column counted in
+    /// UTF-16 code units instead of characters, for LSP-style consumers
+    /// (the Language Server Protocol specifies positions in UTF-16 units).
+    pub fn line_col_utf16(&self, src: &str, offset: usize) -> (usize, usize) {
+        let offset = offset.min(src.len());
+        let line = self.line_for(offset);
+        let col = src[self.line_starts[line]..offset]
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+        (line, col)
+    }
+}
@@ -0,0 +1,561 @@
+//! Markdown (CommonMark) output backend, complementing the HTML [Renderer](crate::Renderer).
+//! Serializes a parsed [Node] tree back into normalized Markdown text so a
+//! document can survive a parse -> render -> parse round trip, the same
+//! role `pulldown-cmark-to-cmark` plays for `pulldown-cmark`.
+//!
+//! Mirrors the HTML backend's shape: [`MarkdownRenderer`] is the Markdown
+//! analogue of [`Renderer`](crate::Renderer) (the sink a node's
+//! serialization writes into), and `render_node` below is the dispatch
+//! point that would ideally be a `render_md(&self, node, fmt: &mut dyn
+//! MarkdownRenderer)` default method on [`NodeValue`](crate::NodeValue)
+//! itself -- with a default body of `fmt.children(node)` -- so that
+//! plugin-defined node types declared outside this file can override their
+//! own Markdown output the same way they already override their HTML
+//! output via `NodeValue::render`.
+//!
+//! That move isn't possible in this checkout: `NodeValue` is defined in
+//! `mdparser::node`, and none of the foundational parser modules it and
+//! its supporting traits (`CoreRule`, `BlockRule`, `InlineRule`,
+//! `Renderer`, `MarkdownIt` itself) live in
+//! (`mdparser::{node, core, block, inline, renderer, main, extset}`) are
+//! present as files under `src/mdparser` here -- there's nothing on disk
+//! to add a default method to. Everything below is written so that
+//! migrating it to per-type `render_md` overrides, once that module
+//! exists, is mechanical: each node case already talks to `fmt` through
+//! the same small set of primitives a `render_md` override would use.
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::footnote::add(parser);
+//! let root = parser.parse("note[^a]\n\n[^a]: the footnote\n");
+//! let md = quickmark::to_commonmark(&root);
+//! assert!(md.contains("[^a]"));
+//! assert!(md.contains("[^a]: the footnote"));
+//! ```
+use crate::plugins::cmark::block::blockquote::Blockquote;
+use crate::plugins::cmark::block::heading::ATXHeading;
+use crate::plugins::cmark::block::list::{BulletList, ListItem, OrderedList};
+use crate::plugins::cmark::block::paragraph::Paragraph;
+use crate::plugins::cmark::inline::backticks::CodeInline;
+use crate::plugins::cmark::inline::emphasis::{Em, Strong};
+use crate::plugins::cmark::inline::image::Image;
+use crate::plugins::cmark::inline::link::Link;
+use crate::plugins::extra::front_matter::FrontMatter;
+use crate::plugins::extra::strikethrough::Strikethrough;
+use crate::plugins::extra::tables::{Table, TableAlign, TableBody, TableCell, TableHead};
+use crate::plugins::extra::tasklist::TaskListMarker;
+use crate::plugins::footnote::{definitions::FootnoteDefinition, references::FootnoteReference};
+use crate::plugins::kagi_plugins::contact_info::ContactInfo;
+use crate::mdparser::inline::builtin::skip_text::Text;
+use crate::Node;
+
+/// Markdown metacharacters that need escaping in plain text nodes so that
+/// re-parsing the output doesn't accidentally resurrect markup.
+const ESCAPE_CHARS: &[char] = &['\\', '*', '_', '`', '[', ']', '<', '>', '#'];
+
+fn escape_markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if ESCAPE_CHARS.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Options controlling [`Node::render_commonmark`]'s output.
+#[derive(Debug, Clone)]
+pub struct CommonmarkOptions {
+    /// Soft-wrap paragraph text at this column; `0` disables wrapping.
+    pub wrap_width: usize,
+    /// Bullet character used for unordered list items (`-`, `*`, or `+`).
+    pub bullet_char: char,
+    /// Emphasis character used for `*em*`/`_em_` markers.
+    pub emphasis_char: char,
+}
+
+impl Default for CommonmarkOptions {
+    fn default() -> Self {
+        Self {
+            wrap_width: 0,
+            bullet_char: '-',
+            emphasis_char: '_',
+        }
+    }
+}
+
+/// State threaded through a Markdown render pass: how deep we are inside
+/// nested lists, the accumulated blockquote `"> "` prefix, the current
+/// output column (for wrapping), and the render options.
+#[derive(Default)]
+struct MarkdownRenderState {
+    opts: CommonmarkOptions,
+    list_depth: usize,
+    blockquote_prefix: String,
+    column: usize,
+    /// One entry per list currently open: `None` for a [`BulletList`], or
+    /// the next item number for an [`OrderedList`] (incremented by each
+    /// [`ListItem`]).
+    list_markers: Vec<Option<u64>>,
+}
+
+/// Write `s` to `out`, tracking the current output column and, when
+/// [`CommonmarkOptions::wrap_width`] is non-zero, soft-wrapping at an
+/// existing space once that width is reached.
+fn emit(state: &mut MarkdownRenderState, out: &mut String, s: &str) {
+    if state.opts.wrap_width == 0 {
+        out.push_str(s);
+        match s.rfind('\n') {
+            Some(idx) => state.column = s[idx + 1..].chars().count(),
+            None => state.column += s.chars().count(),
+        }
+        return;
+    }
+
+    for c in s.chars() {
+        if c == '\n' {
+            out.push('\n');
+            state.column = 0;
+        } else if c == ' ' && state.column >= state.opts.wrap_width {
+            out.push('\n');
+            out.push_str(&state.blockquote_prefix);
+            state.column = state.blockquote_prefix.chars().count();
+        } else {
+            out.push(c);
+            state.column += 1;
+        }
+    }
+}
+
+/// The Markdown-output analogue of [`Renderer`](crate::Renderer): the sink
+/// each node case below writes into. A future per-type `render_md`
+/// override (see the module doc comment) would take `fmt: &mut dyn
+/// MarkdownRenderer` exactly as it's used here.
+pub trait MarkdownRenderer {
+    /// Write `s` verbatim (subject to column tracking/wrapping).
+    fn text(&mut self, s: &str);
+    /// Render `node`'s children in turn -- the default behavior a
+    /// `render_md` override falls back to when it doesn't need to do
+    /// anything special itself.
+    fn children(&mut self, node: &Node);
+    /// Ensure exactly one blank line separates the next block from
+    /// whatever was written before it.
+    fn separate_block(&mut self);
+    /// The active render options (bullet/emphasis char, wrap width).
+    fn opts(&self) -> &CommonmarkOptions;
+    /// Push `extra` onto the blockquote/continuation-line prefix for the
+    /// duration of a nested render, returning the prefix to restore with
+    /// [`MarkdownRenderer::pop_prefix`] afterwards.
+    fn push_prefix(&mut self, extra: &str) -> String;
+    /// Restore a prefix saved by [`MarkdownRenderer::push_prefix`].
+    fn pop_prefix(&mut self, saved: String);
+    /// Enter a list (`None` for a bullet list, `Some(start)` for an
+    /// ordered list starting at `start`); pair with
+    /// [`MarkdownRenderer::pop_list`].
+    fn push_list(&mut self, start: Option<u64>);
+    /// Leave a list entered with [`MarkdownRenderer::push_list`].
+    fn pop_list(&mut self);
+    /// The marker for the next item of the innermost open list (`"- "`,
+    /// `"1. "`, ...), advancing that list's counter if it's ordered.
+    fn next_list_marker(&mut self) -> String;
+    /// Whether the output so far ends with a newline.
+    fn ends_with_newline(&self) -> bool;
+    /// The current blockquote/continuation-line prefix, to be written at
+    /// the start of a new line (e.g. before a table row or list marker).
+    fn prefix(&self) -> String;
+    /// Render `node`'s children into their own string, sharing this
+    /// formatter's wrapping/prefix state but not its output buffer (used
+    /// to materialize a footnote definition's body before indenting it).
+    fn render_into(&mut self, node: &Node) -> String;
+    /// Render `node`'s children in isolation (fresh column/list state, but
+    /// the same options and blockquote prefix) into a standalone string.
+    /// Used for content, like table cells, that must be fully
+    /// materialized before it can be laid out.
+    fn render_standalone(&self, node: &Node) -> String;
+}
+
+struct MarkdownFormatter<'a> {
+    state: &'a mut MarkdownRenderState,
+    out: &'a mut String,
+}
+
+impl MarkdownRenderer for MarkdownFormatter<'_> {
+    fn text(&mut self, s: &str) {
+        emit(self.state, self.out, s);
+    }
+
+    fn children(&mut self, node: &Node) {
+        for child in &node.children {
+            render_node(child, self);
+        }
+    }
+
+    fn separate_block(&mut self) {
+        if self.out.is_empty() {
+            return;
+        }
+        if !self.out.ends_with('\n') {
+            self.text("\n");
+        }
+        if !self.out.ends_with("\n\n") {
+            self.text("\n");
+        }
+        let prefix = self.state.blockquote_prefix.clone();
+        self.text(&prefix);
+    }
+
+    fn opts(&self) -> &CommonmarkOptions {
+        &self.state.opts
+    }
+
+    fn push_prefix(&mut self, extra: &str) -> String {
+        let saved = self.state.blockquote_prefix.clone();
+        self.state.blockquote_prefix.push_str(extra);
+        saved
+    }
+
+    fn pop_prefix(&mut self, saved: String) {
+        self.state.blockquote_prefix = saved;
+    }
+
+    fn push_list(&mut self, start: Option<u64>) {
+        self.state.list_depth += 1;
+        self.state.list_markers.push(start);
+    }
+
+    fn pop_list(&mut self) {
+        self.state.list_markers.pop();
+        self.state.list_depth -= 1;
+    }
+
+    fn next_list_marker(&mut self) -> String {
+        match self.state.list_markers.last_mut() {
+            Some(Some(next)) => {
+                let marker = format!("{}. ", next);
+                *next += 1;
+                marker
+            }
+            _ => format!("{} ", self.state.opts.bullet_char),
+        }
+    }
+
+    fn ends_with_newline(&self) -> bool {
+        self.out.ends_with('\n')
+    }
+
+    fn prefix(&self) -> String {
+        self.state.blockquote_prefix.clone()
+    }
+
+    fn render_into(&mut self, node: &Node) -> String {
+        let mut body = String::new();
+        {
+            let mut scratch = MarkdownFormatter {
+                state: self.state,
+                out: &mut body,
+            };
+            for (idx, child) in node.children.iter().enumerate() {
+                if idx > 0 {
+                    scratch.text("\n\n");
+                }
+                render_node(child, &mut scratch);
+            }
+        }
+        body
+    }
+
+    fn render_standalone(&self, node: &Node) -> String {
+        let mut scratch_state = MarkdownRenderState {
+            opts: self.state.opts.clone(),
+            blockquote_prefix: self.state.blockquote_prefix.clone(),
+            ..Default::default()
+        };
+        let mut out = String::new();
+        let mut scratch = MarkdownFormatter {
+            state: &mut scratch_state,
+            out: &mut out,
+        };
+        scratch.children(node);
+        out
+    }
+}
+
+impl Node {
+    /// Render this node (typically a document [Root](crate::mdparser::core::Root))
+    /// back into normalized CommonMark text, using `opts` to control
+    /// wrapping and bullet/emphasis character choice.
+    pub fn render_commonmark(&self, opts: CommonmarkOptions) -> String {
+        let mut state = MarkdownRenderState {
+            opts,
+            ..Default::default()
+        };
+        let mut out = String::new();
+        let mut fmt = MarkdownFormatter {
+            state: &mut state,
+            out: &mut out,
+        };
+        render_node(self, &mut fmt);
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Render a parsed [Node] tree back into normalized CommonMark text, using
+/// [`CommonmarkOptions::default`].
+pub fn to_commonmark(root: &Node) -> String {
+    root.render_commonmark(CommonmarkOptions::default())
+}
+
+/// Render a table row's cells (already-materialized, one-line-safe text)
+/// as a single `| a | b |` line, honoring the current blockquote prefix.
+fn render_table_row(cells: &[String], fmt: &mut dyn MarkdownRenderer) {
+    let prefix = fmt.prefix();
+    fmt.text(&prefix);
+    fmt.text("|");
+    for cell in cells {
+        fmt.text(" ");
+        fmt.text(cell);
+        fmt.text(" |");
+    }
+    fmt.text("\n");
+}
+
+/// Render a table cell's inline content into a single-line, pipe-escaped
+/// string suitable for a pipe-table row.
+fn render_table_cell(cell: &Node, fmt: &dyn MarkdownRenderer) -> String {
+    fmt.render_standalone(cell)
+        .replace('\n', " ")
+        .replace('|', "\\|")
+        .trim()
+        .to_string()
+}
+
+/// Wrap `content` in the shortest run of backticks that isn't itself a
+/// substring of `content`, padding with a space on each side when needed so
+/// the delimiters aren't confused with the content's own backticks (the same
+/// rule CommonMark uses for code spans).
+fn render_code_span(content: &str) -> String {
+    let mut fence_len = 1;
+    while content.contains(&"`".repeat(fence_len)) {
+        fence_len += 1;
+    }
+    let fence = "`".repeat(fence_len);
+    let needs_padding = content.starts_with('`')
+        || content.ends_with('`')
+        || content.starts_with(' ')
+        || content.ends_with(' ');
+    if needs_padding {
+        format!("{fence} {content} {fence}")
+    } else {
+        format!("{fence}{content}{fence}")
+    }
+}
+
+fn align_marker(align: TableAlign) -> &'static str {
+    match align {
+        TableAlign::None => "---",
+        TableAlign::Left => ":--",
+        TableAlign::Center => ":-:",
+        TableAlign::Right => "--:",
+    }
+}
+
+/// Dispatch `node` to its Markdown serialization. This is the stand-in for
+/// what would otherwise be `node.render_md(fmt)` calling through a
+/// per-type [`NodeValue`](crate::NodeValue) override -- see the module doc
+/// comment for why that's not available in this checkout.
+fn render_node(node: &Node, fmt: &mut dyn MarkdownRenderer) {
+    if let Some(front_matter) = node.cast::<FrontMatter>() {
+        fmt.text("---\n");
+        fmt.text(&front_matter.content.clone());
+        if !front_matter.content.ends_with('\n') {
+            fmt.text("\n");
+        }
+        fmt.text("---\n");
+        return;
+    }
+
+    if let Some(text) = node.cast::<Text>() {
+        fmt.text(&escape_markdown(&text.content));
+        return;
+    }
+
+    if node.is::<Strikethrough>() {
+        fmt.text("~~");
+        fmt.children(node);
+        fmt.text("~~");
+        return;
+    }
+
+    if node.is::<Em>() {
+        let marker = fmt.opts().emphasis_char.to_string();
+        fmt.text(&marker);
+        fmt.children(node);
+        fmt.text(&marker);
+        return;
+    }
+
+    if node.is::<Strong>() {
+        let marker = fmt.opts().emphasis_char.to_string().repeat(2);
+        fmt.text(&marker);
+        fmt.children(node);
+        fmt.text(&marker);
+        return;
+    }
+
+    if let Some(code) = node.cast::<CodeInline>() {
+        fmt.text(&render_code_span(&code.content));
+        return;
+    }
+
+    if let Some(link) = node.cast::<Link>() {
+        fmt.text("[");
+        fmt.children(node);
+        fmt.text("](");
+        fmt.text(&link.url);
+        if let Some(title) = &link.title {
+            fmt.text(&format!(" \"{}\"", title));
+        }
+        fmt.text(")");
+        return;
+    }
+
+    if let Some(image) = node.cast::<Image>() {
+        fmt.text(&format!("![{}](", escape_markdown(&image.alt)));
+        fmt.text(&image.url);
+        if let Some(title) = &image.title {
+            fmt.text(&format!(" \"{}\"", title));
+        }
+        fmt.text(")");
+        return;
+    }
+
+    if let Some(heading) = node.cast::<ATXHeading>() {
+        fmt.separate_block();
+        fmt.text(&format!("{} ", "#".repeat(heading.level as usize)));
+        fmt.children(node);
+        return;
+    }
+
+    if let Some(marker) = node.cast::<TaskListMarker>() {
+        fmt.text(if marker.checked { "[x] " } else { "[ ] " });
+        return;
+    }
+
+    if let Some(reference) = node.cast::<FootnoteReference>() {
+        let label = reference
+            .label
+            .clone()
+            .unwrap_or_else(|| reference.def_id.to_string());
+        fmt.text(&format!("[^{}]", label));
+        return;
+    }
+
+    if let Some(definition) = node.cast::<FootnoteDefinition>() {
+        fmt.separate_block();
+        let label = definition
+            .label
+            .clone()
+            .unwrap_or_else(|| definition.def_id.unwrap_or(0).to_string());
+        let body = fmt.render_into(node);
+        fmt.text(&format!("[^{}]: {}\n", label, body.replace('\n', "\n    ")));
+        return;
+    }
+
+    if let Some(contact) = node.cast::<ContactInfo>() {
+        fmt.text(&format!("<{}{}>", contact.prefix, contact.content));
+        return;
+    }
+
+    if node.is::<Paragraph>() {
+        fmt.separate_block();
+        fmt.children(node);
+        return;
+    }
+
+    if node.is::<Blockquote>() {
+        fmt.separate_block();
+        let saved_prefix = fmt.push_prefix("> ");
+        fmt.text("> ");
+        fmt.children(node);
+        fmt.pop_prefix(saved_prefix);
+        return;
+    }
+
+    if node.is::<Table>() {
+        fmt.separate_block();
+
+        let mut aligns = Vec::new();
+        let mut header_cells = Vec::new();
+        let mut body_rows: Vec<Vec<String>> = Vec::new();
+
+        for section in &node.children {
+            if section.is::<TableHead>() {
+                if let Some(row) = section.children.first() {
+                    for cell in &row.children {
+                        if let Some(table_cell) = cell.cast::<TableCell>() {
+                            aligns.push(table_cell.align);
+                            header_cells.push(render_table_cell(cell, &*fmt));
+                        }
+                    }
+                }
+            } else if section.is::<TableBody>() {
+                for row in &section.children {
+                    let cells = row
+                        .children
+                        .iter()
+                        .map(|cell| render_table_cell(cell, &*fmt))
+                        .collect();
+                    body_rows.push(cells);
+                }
+            }
+        }
+
+        render_table_row(&header_cells, fmt);
+        let delim: Vec<String> = aligns.iter().map(|a| align_marker(*a).to_string()).collect();
+        render_table_row(&delim, fmt);
+        for row in &body_rows {
+            render_table_row(row, fmt);
+        }
+        return;
+    }
+
+    if node.is::<BulletList>() {
+        fmt.separate_block();
+        fmt.push_list(None);
+        fmt.children(node);
+        fmt.pop_list();
+        return;
+    }
+
+    if let Some(ordered) = node.cast::<OrderedList>() {
+        fmt.separate_block();
+        fmt.push_list(Some(ordered.start));
+        fmt.children(node);
+        fmt.pop_list();
+        return;
+    }
+
+    if node.is::<ListItem>() {
+        let prefix = fmt.prefix();
+        fmt.text(&prefix);
+        let marker = fmt.next_list_marker();
+        let indent = " ".repeat(marker.chars().count());
+        fmt.text(&marker);
+        let saved_prefix = fmt.push_prefix(&indent);
+        fmt.children(node);
+        fmt.pop_prefix(saved_prefix);
+        if !fmt.ends_with_newline() {
+            fmt.text("\n");
+        }
+        return;
+    }
+
+    // anything else (containers we don't have a dedicated form for, the
+    // document root, etc.) just recurses over its children
+    fmt.children(node);
+}
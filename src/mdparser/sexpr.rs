@@ -0,0 +1,50 @@
+//! Lisp-style structural dump of a parsed [Node] tree, for debugging and
+//! golden-file testing. Unlike [Renderer](crate::Renderer)/[NodeValue::render](crate::NodeValue::render),
+//! this is not configurable per node type - it's a generic traversal over
+//! whatever node name, attrs, and children are present, so every plugin's
+//! nodes show up without extra work.
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! let root = parser.parse("# hi");
+//! let dump = quickmark::to_sexpr(&root);
+//! assert!(dump.starts_with("(quickmark::mdparser::core::root::Root"));
+//! ```
+use crate::mdparser::inline::builtin::skip_text::Text;
+use crate::Node;
+
+/// Render a parsed [Node] tree as an indented S-expression, with each child
+/// form on its own line, indented two spaces per level. The head symbol of
+/// each form is [`Node::name`](crate::Node), the node's full type path
+/// (e.g. `quickmark::mdparser::core::root::Root`), `attrs` are printed as
+/// keyword pairs, and [`Text`] leaves are printed as a single quoted
+/// literal with embedded newlines normalized to spaces so a leaf stays on
+/// one line.
+pub fn to_sexpr(node: &Node) -> String {
+    let mut out = String::new();
+    write_sexpr(node, 0, &mut out);
+    out
+}
+
+fn write_sexpr(node: &Node, depth: usize, out: &mut String) {
+    out.push_str(&"  ".repeat(depth));
+    out.push('(');
+    out.push_str(node.name());
+
+    for (key, value) in &node.attrs {
+        out.push_str(&format!(" :{} {:?}", key, value));
+    }
+
+    if let Some(text) = node.cast::<Text>() {
+        out.push(' ');
+        out.push_str(&format!("{:?}", text.content.replace('\n', " ")));
+    }
+
+    for child in &node.children {
+        out.push('\n');
+        write_sexpr(child, depth + 1, out);
+    }
+
+    out.push(')');
+}
@@ -0,0 +1,49 @@
+//! A traversal variant that pairs each node visit with a matching "on
+//! exit" callback, so a rule can maintain depth-scoped state across a
+//! single walk instead of making two passes.
+//!
+//! This complements the plain `walk`/`walk_mut` on [Node]: those only see
+//! a node "on the way in", so a rule that needs to know when it has left a
+//! subtree has to track that itself. The concrete motivation is
+//! `typographer`/`smartquotes`: replacements like `(c)` -> `©` must not
+//! apply to text inside a linkified/autolinked node, which means
+//! incrementing a `link_level` counter on entering such a node and
+//! decrementing it again on exit. `acc` is threaded through both
+//! callbacks for exactly that purpose; see
+//! [`typographer`](crate::plugins::extra::typographer) and
+//! [`smartquotes`](crate::plugins::extra::smartquotes), which both use it
+//! this way.
+use crate::Node;
+
+impl Node {
+    /// Walk this node and its descendants depth-first, calling `on_enter`
+    /// before descending into a node's children and `on_exit` immediately
+    /// after, both given the current depth and shared mutable access to
+    /// `acc`.
+    pub fn walk_mut_enter_exit<T>(
+        &mut self,
+        acc: &mut T,
+        mut on_enter: impl FnMut(&mut Node, u32, &mut T),
+        mut on_exit: impl FnMut(&mut Node, u32, &mut T),
+    ) {
+        fn visit<T>(
+            node: &mut Node,
+            depth: u32,
+            acc: &mut T,
+            on_enter: &mut impl FnMut(&mut Node, u32, &mut T),
+            on_exit: &mut impl FnMut(&mut Node, u32, &mut T),
+        ) {
+            on_enter(node, depth, acc);
+            for child in &mut node.children {
+                visit(child, depth + 1, acc, on_enter, on_exit);
+            }
+            on_exit(node, depth, acc);
+        }
+
+        on_enter(self, 0, acc);
+        for child in &mut self.children {
+            visit(child, 1, acc, &mut on_enter, &mut on_exit);
+        }
+        on_exit(self, 0, acc);
+    }
+}
@@ -0,0 +1,51 @@
+//! A PyO3-exposed mirror of the internal [`Node`](crate::Node) tree,
+//! returned by [`MDParser::tree`](crate::MDParser::tree) for Python callers
+//! that want to walk the parsed AST directly instead of working from
+//! rendered HTML.
+use pyo3::prelude::*;
+
+use crate::line_index::LineIndex;
+
+/// One node of a [`tree`](crate::MDParser::tree) result: the node's debug
+/// name, its resolved `(line, col)` span when sourcepos is enabled (`None`
+/// for a node with no `srcmap`, e.g. when the `sourcepos` plugin isn't
+/// active), and its children.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Node {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub line: Option<usize>,
+    #[pyo3(get)]
+    pub col: Option<usize>,
+    #[pyo3(get)]
+    pub end_line: Option<usize>,
+    #[pyo3(get)]
+    pub end_col: Option<usize>,
+    #[pyo3(get)]
+    pub children: Vec<Py<Node>>,
+}
+
+/// Build a [`Node`] (with empty `children`, which the caller fills in while
+/// walking) from a parsed [`crate::Node`], resolving its `srcmap` (if any)
+/// to a `(line, col)` span via `index`.
+pub fn create_node(_py: Python<'_>, node: &crate::Node, src: &str, index: &LineIndex) -> Node {
+    let (line, col, end_line, end_col) = match node.srcmap {
+        Some((start, end)) => {
+            let (line, col) = index.line_col(src, start);
+            let (end_line, end_col) = index.line_col(src, end);
+            (Some(line), Some(col), Some(end_line), Some(end_col))
+        }
+        None => (None, None, None, None),
+    };
+
+    Node {
+        name: node.name(),
+        line,
+        col,
+        end_line,
+        end_col,
+        children: Vec::new(),
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{mdparser::extset::MarkdownItExt, plugins::kagi_plugins::citation::CitationQM};
+use crate::{
+    mdparser::extset::MarkdownItExt,
+    plugins::kagi_plugins::{citation::CitationQM, math::MathMacro},
+};
 use pyo3::prelude::*;
 
 // NOTE(Rehan): instructions on creating new plugin:
@@ -32,6 +35,39 @@ impl Plugin {
     }
 }
 
+#[pyclass(extends = Plugin)]
+#[derive(Debug, Clone, Copy)]
+pub struct AutolinkUrlExtensionPlugin {
+    #[pyo3(get)]
+    pub enabled: bool,
+    /// Also autolink bare `www.`-prefixed URLs (assumed `https://`), not
+    /// just ones with an explicit `http://`/`https://` scheme.
+    #[pyo3(get)]
+    pub match_www: bool,
+}
+
+impl MarkdownItExt for AutolinkUrlExtensionPlugin {}
+
+impl Default for AutolinkUrlExtensionPlugin {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            match_www: true,
+        }
+    }
+}
+#[pymethods]
+impl AutolinkUrlExtensionPlugin {
+    #[new]
+    #[pyo3(signature = (enabled=true, match_www=true))]
+    fn new(enabled: bool, match_www: bool) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(Plugin {
+            name: "autolink_url".to_string(),
+        })
+        .add_subclass(AutolinkUrlExtensionPlugin { enabled, match_www })
+    }
+}
+
 #[pyclass(extends = Plugin)]
 #[derive(Debug, Clone, Copy)]
 pub struct LinkExtensionPlugin {
@@ -136,60 +172,97 @@ impl ImageExtensionPlugin {
 }
 
 #[pyclass(extends = Plugin)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct InlineMathExtensionPlugin {
     #[pyo3(get)]
     pub cache: bool,
+    /// User-defined macros (e.g. `\RR`, `\abs{x}`) available to inline math
+    /// in this document.
+    #[pyo3(get)]
+    pub macros: Vec<MathMacro>,
 }
 
 impl MarkdownItExt for InlineMathExtensionPlugin {}
 
 impl Default for InlineMathExtensionPlugin {
     fn default() -> Self {
-        Self { cache: true }
+        Self {
+            cache: true,
+            macros: Vec::new(),
+        }
     }
 }
 #[pymethods]
 impl InlineMathExtensionPlugin {
     #[new]
-    fn new(cache: bool) -> PyClassInitializer<Self> {
+    #[pyo3(signature = (cache=true, macros=vec![]))]
+    fn new(cache: bool, macros: Vec<MathMacro>) -> PyClassInitializer<Self> {
         PyClassInitializer::from(Plugin {
             name: "inline_math".to_string(),
         })
-        .add_subclass(InlineMathExtensionPlugin { cache })
+        .add_subclass(InlineMathExtensionPlugin { cache, macros })
     }
 }
 
 #[pyclass(extends = Plugin)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct DisplayMathExtensionPlugin {
     #[pyo3(get)]
     pub cache: bool,
+    /// User-defined macros (e.g. `\RR`, `\abs{x}`) available to display
+    /// math in this document.
+    #[pyo3(get)]
+    pub macros: Vec<MathMacro>,
 }
 
 impl MarkdownItExt for DisplayMathExtensionPlugin {}
 
 impl Default for DisplayMathExtensionPlugin {
     fn default() -> Self {
-        Self { cache: true }
+        Self {
+            cache: true,
+            macros: Vec::new(),
+        }
     }
 }
 #[pymethods]
 impl DisplayMathExtensionPlugin {
     #[new]
-    fn new(cache: bool) -> PyClassInitializer<Self> {
+    #[pyo3(signature = (cache=true, macros=vec![]))]
+    fn new(cache: bool, macros: Vec<MathMacro>) -> PyClassInitializer<Self> {
         PyClassInitializer::from(Plugin {
             name: "display_math".to_string(),
         })
-        .add_subclass(DisplayMathExtensionPlugin { cache })
+        .add_subclass(DisplayMathExtensionPlugin { cache, macros })
     }
 }
 
 #[pyclass(extends = Plugin)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct InkjetPlugin {
     #[pyo3(get)]
     pub pygments_classes: bool,
+    /// Name of a bundled syntect theme to use when `pygments_classes` is
+    /// `false`. Falls back to a bundled default theme when `None`.
+    #[pyo3(get)]
+    pub theme: Option<String>,
+    /// Name of a bundled inline-style color theme (e.g. `"github-dark"`)
+    /// for the tree-sitter highlighter used when `pygments_classes` is
+    /// `true`. When set, each highlighted token gets a `style="color:..."`
+    /// attribute instead of a CSS class, so the output needs no
+    /// stylesheet. `None` keeps the existing class-based output.
+    #[pyo3(get)]
+    pub style_theme: Option<String>,
+    /// Language tokens (e.g. `"dot"`, `"graphviz"`, `"mermaid"`) that should
+    /// bypass syntax highlighting and render as a diagram instead, mirroring
+    /// nml's dedicated graphviz element.
+    #[pyo3(get)]
+    pub diagram_renderers: Vec<String>,
+    /// Path (or bare name on `$PATH`) to a `dot`-compatible layout binary
+    /// used to render `dot`/`graphviz` fences to inline SVG. Falls back to
+    /// `"dot"` when `None`.
+    #[pyo3(get)]
+    pub graphviz_binary_path: Option<String>,
 }
 
 impl MarkdownItExt for InkjetPlugin {}
@@ -198,16 +271,39 @@ impl Default for InkjetPlugin {
     fn default() -> Self {
         Self {
             pygments_classes: true,
+            theme: None,
+            style_theme: None,
+            diagram_renderers: vec!["dot".to_string(), "graphviz".to_string(), "mermaid".to_string()],
+            graphviz_binary_path: None,
         }
     }
 }
 #[pymethods]
 impl InkjetPlugin {
     #[new]
-    fn new(pygments_classes: bool) -> PyClassInitializer<Self> {
+    #[pyo3(signature = (
+        pygments_classes=true,
+        theme=None,
+        style_theme=None,
+        diagram_renderers=vec!["dot".to_string(), "graphviz".to_string(), "mermaid".to_string()],
+        graphviz_binary_path=None,
+    ))]
+    fn new(
+        pygments_classes: bool,
+        theme: Option<String>,
+        style_theme: Option<String>,
+        diagram_renderers: Vec<String>,
+        graphviz_binary_path: Option<String>,
+    ) -> PyClassInitializer<Self> {
         PyClassInitializer::from(Plugin {
             name: "inkjet".to_string(),
         })
-        .add_subclass(InkjetPlugin { pygments_classes })
+        .add_subclass(InkjetPlugin {
+            pygments_classes,
+            theme,
+            style_theme,
+            diagram_renderers,
+            graphviz_binary_path,
+        })
     }
 }
@@ -3,31 +3,355 @@
 //! ` ```lang ` or `~~~lang`
 //!
 //! <https://spec.commonmark.org/0.30/#code-fence>
+//!
+//! The info string also accepts a Pandoc/rustdoc-style curly-brace
+//! attribute block, e.g. `` ```{.rust .line-numbers #example startFrom="100"} ``:
+//! a `.foo` token adds an extra class, `#foo` sets the element `id`, and
+//! `key=value`/`key="value"` becomes an HTML attribute. The first `.class`
+//! (brace form) or first bare word (legacy form) still determines the
+//! language; remaining legacy-form tags (comma- or space-separated, e.g.
+//! `rust,ignore`) and brace-form `.class` tokens are both collected as
+//! classes, so rustdoc's `ignore`/`no_run` tags are recognized either way
+//! (see [`set_playground_config`]).
+//!
+//! Malformed fences (one auto-closed by end of document/parent block
+//! instead of its own closing marker, or a backtick fence whose info
+//! string illegally contains a backtick) are recorded as a
+//! [`FenceDiagnostic`] rather than silently rendered/dropped as if nothing
+//! were wrong; see [`FenceDiagnostics`].
 use crate::common::utils::unescape_all;
 use crate::mdparser::block::{BlockRule, BlockState};
-use crate::mdparser::extset::MarkdownItExt;
+use crate::mdparser::extset::{MarkdownItExt, RootExt};
 use crate::{MarkdownIt, Node, NodeValue, Renderer};
+use once_cell::sync::Lazy;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{
+    styled_line_to_highlighted_html, ClassStyle, ClassedHTMLGenerator, IncludeBackground,
+};
+use syntect::parsing::SyntaxSet;
+use v_htmlescape;
+
+// bundled fallback so `HighlightMode::InlineStyle` with no configured theme
+// still produces self-contained, styled output
+const DEFAULT_SYNTECT_THEME: &str = "InspiredGitHub";
+
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
+/// Built-in (non-inkjet) highlighting mode for fence content; see
+/// [`FenceSettings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HighlightMode {
+    /// No built-in highlighting: `content` is emitted as a single escaped
+    /// text node, same as before this feature existed, typically left to
+    /// an external inkjet/Python post-pass.
+    #[default]
+    None,
+    /// Inline `style="..."` spans resolved from a syntect `Theme`, for
+    /// standalone HTML with no external stylesheet.
+    InlineStyle,
+    /// `<span class="...">` spans via syntect's `ClassedHTMLGenerator`, for
+    /// CSS-themed pages.
+    Classed,
+}
+
+/// Highlight `content` with syntect, resolving `lang_name` to a syntax
+/// definition (falling back to plain text) and `theme_name` to a bundled
+/// theme (falling back to [`DEFAULT_SYNTECT_THEME`]), emitting inline
+/// `style="..."` spans so the output needs no external stylesheet.
+fn highlight_inline_style(content: &str, lang_name: &str, theme_name: Option<&str>) -> String {
+    let syntax = SYNTAX_SET
+        .find_syntax_by_token(lang_name)
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+    let theme = theme_name
+        .and_then(|name| THEME_SET.themes.get(name))
+        .or_else(|| THEME_SET.themes.get(DEFAULT_SYNTECT_THEME))
+        .expect("bundled default syntect theme must be present");
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut html = String::new();
+    for line in content.lines() {
+        let Ok(regions) = highlighter.highlight_line(line, &SYNTAX_SET) else {
+            html.push_str(&v_htmlescape::escape(line).to_string());
+            html.push('\n');
+            continue;
+        };
+        html.push_str(
+            &styled_line_to_highlighted_html(&regions[..], IncludeBackground::No)
+                .unwrap_or_default(),
+        );
+        html.push('\n');
+    }
+    html
+}
+
+/// Highlight `content` with syntect, resolving `lang_name` the same way as
+/// [`highlight_inline_style`], emitting `<span class="...">` spans for a
+/// CSS-themed page instead of inline styles.
+fn highlight_classed(content: &str, lang_name: &str) -> String {
+    let syntax = SYNTAX_SET
+        .find_syntax_by_token(lang_name)
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+
+    let mut generator =
+        ClassedHTMLGenerator::new_with_class_style(syntax, &SYNTAX_SET, ClassStyle::Spaced);
+    for line in content.lines() {
+        // the generator wants each line to include its trailing newline
+        let _ = generator.parse_html_for_line_which_includes_newline(&format!("{}\n", line));
+    }
+    generator.finalize()
+}
+
+/// Attributes parsed from a fence's info string. For the Pandoc/rustdoc-style
+/// `{...}` attribute block, `classes` holds every `.class` token (the first
+/// of which, the language, included); for the legacy form, it holds the
+/// tags after the language (e.g. `ignore` in `rust,ignore`), and `id` and
+/// `attrs` stay empty.
+#[derive(Debug, Clone, Default)]
+pub struct FenceAttrs {
+    pub classes: Vec<String>,
+    pub id: Option<String>,
+    /// `key=value` / `key="value"` attributes, in source order.
+    pub attrs: Vec<(String, String)>,
+}
+
+/// Split an attribute block's interior on whitespace, keeping
+/// double-quoted values intact (so `key="a b"` stays one token).
+fn split_attr_tokens(s: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in s.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Parse a fence's (already-unescaped) info string into a language name and
+/// its [`FenceAttrs`], supporting both the legacy bare-word form and the
+/// Pandoc/rustdoc-style `{...}` attribute block.
+fn parse_fence_info(info: &str) -> (String, FenceAttrs) {
+    let trimmed = info.trim();
+
+    let Some(inner) = trimmed
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+    else {
+        // rustdoc-style legacy info strings tack on comma- or
+        // whitespace-separated tags after the language, e.g. `rust,ignore`
+        // or `rust ignore`; keep them as classes so later passes (like the
+        // playground "Run" link) can check for `ignore`/`no_run`.
+        let mut tokens = trimmed
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|t| !t.is_empty());
+        let lang_name = tokens.next().unwrap_or("").to_string();
+        let classes = tokens.map(|t| t.to_string()).collect();
+        return (lang_name, FenceAttrs { classes, ..Default::default() });
+    };
+
+    let mut attrs = FenceAttrs::default();
+    let mut lang_name = None;
+
+    for token in split_attr_tokens(inner) {
+        if let Some(class) = token.strip_prefix('.') {
+            if lang_name.is_none() {
+                lang_name = Some(class.to_string());
+            }
+            attrs.classes.push(class.to_string());
+        } else if let Some(id) = token.strip_prefix('#') {
+            attrs.id = Some(id.to_string());
+        } else if let Some((key, value)) = token.split_once('=') {
+            attrs
+                .attrs
+                .push((key.to_string(), value.trim_matches('"').to_string()));
+        }
+        // bare words inside a brace block carry no meaning here (unlike the
+        // legacy non-brace form, where the first one names the language)
+    }
+
+    (lang_name.unwrap_or_default(), attrs)
+}
+
+/// Attribute keys already converted to `&'static str` by [`leak_key`],
+/// deduplicated by content so a key string already seen is reused instead
+/// of leaking a fresh copy. Bounds the leak to the number of *distinct*
+/// attribute-key strings seen over the process's lifetime, rather than one
+/// leak per render of every document that uses a `key=value` fence
+/// attribute.
+static INTERNED_ATTR_KEYS: Lazy<std::sync::Mutex<std::collections::HashSet<&'static str>>> =
+    Lazy::new(|| std::sync::Mutex::new(std::collections::HashSet::new()));
+
+/// Convert an owned attribute key to `&'static str` so it can sit alongside
+/// the rest of [`Node::attrs`], which (like every other plugin's output
+/// attributes) is keyed by string literals rather than owned strings.
+/// Interns through [`INTERNED_ATTR_KEYS`] so re-rendering the same (or a
+/// different) document doesn't leak a new copy of a key it has already
+/// seen.
+fn leak_key(key: String) -> &'static str {
+    let mut interned = INTERNED_ATTR_KEYS.lock().unwrap();
+    if let Some(existing) = interned.get(key.as_str()) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(key.into_boxed_str());
+    interned.insert(leaked);
+    leaked
+}
+
+/// Apply rustdoc's line-hiding convention: a line that is exactly `#` or
+/// starts with `# ` is boilerplate that's part of the real program but
+/// hidden from the rendered output (the `#`/`# ` prefix is stripped from
+/// what's kept); a line starting with `##` is an escape for a literal line
+/// beginning with `#`, shown as-is (with one `#` stripped) in both outputs.
+/// Returns `(display_content, full_content)`.
+fn split_hidden_lines(content: &str) -> (String, String) {
+    let mut display_lines = Vec::new();
+    let mut full_lines = Vec::new();
+
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix("##") {
+            let literal = format!("#{}", rest);
+            display_lines.push(literal.clone());
+            full_lines.push(literal);
+        } else if line == "#" || line.starts_with("# ") {
+            let rest = line.strip_prefix('#').unwrap_or(line);
+            let rest = rest.strip_prefix(' ').unwrap_or(rest);
+            full_lines.push(rest.to_string());
+        } else {
+            display_lines.push(line.to_string());
+            full_lines.push(line.to_string());
+        }
+    }
+
+    let trailing_newline = content.ends_with('\n');
+    let mut display_content = display_lines.join("\n");
+    let mut full_content = full_lines.join("\n");
+    if trailing_newline {
+        display_content.push('\n');
+        full_content.push('\n');
+    }
+    (display_content, full_content)
+}
+
+/// [`FenceDiagnostic::code`] for a fence auto-closed by end of document or
+/// parent block rather than its own closing marker.
+const UNTERMINATED_FENCE_CODE: &str = "unterminated-fence";
+/// [`FenceDiagnostic::code`] for a backtick fence whose info string
+/// illegally contains a backtick (CommonMark §4.5): such a line isn't a
+/// fence at all, so it's reparsed by whatever rule would otherwise claim
+/// it, but the mistake is still worth flagging.
+const BACKTICK_IN_INFO_CODE: &str = "backtick-fence-info-has-backtick";
+
+/// A structured, machine-readable warning about a malformed code fence,
+/// collected during parsing instead of silently auto-closing or dropping
+/// it as if nothing were wrong. See [`FenceDiagnostics`] for how to
+/// retrieve these after parsing.
+#[derive(Debug, Clone)]
+pub struct FenceDiagnostic {
+    /// Machine-readable code (e.g. [`UNTERMINATED_FENCE_CODE`]) for
+    /// editors/CI to key off of instead of parsing `message`.
+    pub code: &'static str,
+    pub message: String,
+    /// 0-indexed, inclusive first line.
+    pub line_start: usize,
+    /// 0-indexed, exclusive last line.
+    pub line_end: usize,
+}
+
+/// Every [`FenceDiagnostic`] collected while parsing a document. Retrieve
+/// with `root.ext.get::<FenceDiagnostics>()` once parsing completes (see
+/// [`RootExt`]); absent when no fence in the document was malformed.
+#[derive(Debug, Clone, Default)]
+pub struct FenceDiagnostics(pub Vec<FenceDiagnostic>);
+impl RootExt for FenceDiagnostics {}
+
+/// Optional Rust-Playground-style "Run" link for executable code fences;
+/// see [`set_playground_config`]. Absent by default, so default rendering
+/// is unchanged.
+#[derive(Debug, Clone)]
+pub struct PlaygroundConfig {
+    /// Base URL; the full fence source is percent-encoded into its `code`
+    /// query parameter, e.g. `https://play.rust-lang.org/?edition=2021`.
+    pub base_url: String,
+    /// Language tokens (case-insensitive) this playground accepts.
+    pub languages: Vec<String>,
+}
+impl MarkdownItExt for PlaygroundConfig {}
+
+/// Build a playground "Run" URL for `source` against `config`, percent-
+/// encoding the full fence source into a `code` query parameter.
+fn playground_url(config: &PlaygroundConfig, source: &str) -> String {
+    let separator = if config.base_url.contains('?') { '&' } else { '?' };
+    format!(
+        "{}{}code={}",
+        config.base_url,
+        separator,
+        utf8_percent_encode(source, NON_ALPHANUMERIC)
+    )
+}
 
 #[derive(Debug)]
 pub struct CodeFence {
     pub info: String,
     pub marker: char,
     pub marker_len: usize,
-    pub content: String,
+    /// Content as shown in the rendered `<code>` block, with rustdoc-style
+    /// `#`-hidden lines (see [`split_hidden_lines`]) removed when this
+    /// fence's language is gated by [`FenceSettings::hidden_line_languages`].
+    pub display_content: String,
+    /// The complete program: same as `display_content` when line-hiding
+    /// doesn't apply to this fence, otherwise the hidden lines are restored
+    /// (with their `#`/`# ` prefix stripped) for copy-to-clipboard /
+    /// doctest consumers.
+    pub full_content: String,
     pub lang_prefix: &'static str,
+    pub lang_name: String,
+    pub fence_attrs: FenceAttrs,
+    pub highlight_mode: HighlightMode,
+    /// Theme for [`HighlightMode::InlineStyle`]; only meaningful in that
+    /// mode, and falls back to [`DEFAULT_SYNTECT_THEME`] when `None`.
+    pub theme_name: Option<String>,
+    /// Rust-Playground-style "Run" link, when [`PlaygroundConfig`] is set,
+    /// `lang_name` is one of its allowed languages, and this fence isn't
+    /// tagged `ignore`/`no_run`.
+    pub playground_url: Option<String>,
 }
 
 impl NodeValue for CodeFence {
     fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
-        let info = unescape_all(&self.info);
-        let mut split = info.split_whitespace();
-        let lang_name = split.next().unwrap_or("");
         let mut attrs = node.attrs.clone();
-        let class;
 
-        if !lang_name.is_empty() {
-            class = format!("{}{}", self.lang_prefix, lang_name);
-            attrs.push(("class", class));
+        if !self.lang_name.is_empty() {
+            attrs.push(("class", format!("{}{}", self.lang_prefix, self.lang_name)));
+        }
+        for class in &self.fence_attrs.classes {
+            attrs.push(("class", class.clone()));
+        }
+        if let Some(id) = &self.fence_attrs.id {
+            attrs.push(("id", id.clone()));
+        }
+        for (key, value) in &self.fence_attrs.attrs {
+            attrs.push((leak_key(key.clone()), value.clone()));
+        }
+        if self.full_content != self.display_content {
+            attrs.push(("data-source", self.full_content.clone()));
         }
 
         // NOTE(Rehan): this is what our python code highlighting extension has wrapped around the actual highlighted code
@@ -42,13 +366,28 @@ impl NodeValue for CodeFence {
         fmt.cr();
         fmt.open("div", &[("class", "codehilite".to_string())]);
         fmt.open("span", &[("class", "filename".to_string())]);
-        fmt.text(lang_name);
+        fmt.text(&self.lang_name);
         fmt.close("span");
+        if let Some(url) = &self.playground_url {
+            fmt.open("a", &[("class", "playground-run".to_string()), ("href", url.clone())]);
+            fmt.text("Run");
+            fmt.close("a");
+        }
         fmt.open("pre", &[]);
         fmt.open("span", &[]);
         fmt.close("span");
-        fmt.open("code", &[]);
-        fmt.text(&self.content);
+        fmt.open("code", &attrs);
+        match self.highlight_mode {
+            HighlightMode::None => fmt.text(&self.display_content),
+            HighlightMode::InlineStyle => fmt.text_raw(&highlight_inline_style(
+                &self.display_content,
+                &self.lang_name,
+                self.theme_name.as_deref(),
+            )),
+            HighlightMode::Classed => {
+                fmt.text_raw(&highlight_classed(&self.display_content, &self.lang_name))
+            }
+        }
         fmt.close("code");
         fmt.close("pre");
         fmt.close("div");
@@ -56,13 +395,25 @@ impl NodeValue for CodeFence {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct FenceSettings(&'static str);
+#[derive(Debug, Clone)]
+struct FenceSettings {
+    lang_prefix: &'static str,
+    highlight_mode: HighlightMode,
+    theme_name: Option<String>,
+    /// Language tokens (case-insensitive) gated for rustdoc-style `#`-line
+    /// hiding, so non-Rust users aren't affected by default.
+    hidden_line_languages: Vec<String>,
+}
 impl MarkdownItExt for FenceSettings {}
 
 impl Default for FenceSettings {
     fn default() -> Self {
-        Self("language-")
+        Self {
+            lang_prefix: "language-",
+            highlight_mode: HighlightMode::default(),
+            theme_name: None,
+            hidden_line_languages: vec!["rust".to_string()],
+        }
     }
 }
 
@@ -71,24 +422,66 @@ pub fn add(md: &mut MarkdownIt) {
 }
 
 pub fn set_lang_prefix(md: &mut MarkdownIt, lang_prefix: &'static str) {
-    md.ext.insert(FenceSettings(lang_prefix));
+    let mut config = md.ext.get::<FenceSettings>().cloned().unwrap_or_default();
+    config.lang_prefix = lang_prefix;
+    md.ext.insert(config);
+}
+
+/// Opt into built-in (non-inkjet) syntax highlighting of fence content at
+/// render time, colorizing in a single pass instead of relying on an
+/// external post-pass. `theme_name` only applies to
+/// [`HighlightMode::InlineStyle`]; pass `None` to use the bundled default
+/// theme.
+pub fn set_highlight_mode(md: &mut MarkdownIt, highlight_mode: HighlightMode, theme_name: Option<String>) {
+    let mut config = md.ext.get::<FenceSettings>().cloned().unwrap_or_default();
+    config.highlight_mode = highlight_mode;
+    config.theme_name = theme_name;
+    md.ext.insert(config);
+}
+
+/// Configure which language tokens (case-insensitive) get rustdoc-style
+/// `#`-line hiding (see [`split_hidden_lines`]). Defaults to `["rust"]`.
+pub fn set_hidden_line_languages(md: &mut MarkdownIt, languages: Vec<String>) {
+    let mut config = md.ext.get::<FenceSettings>().cloned().unwrap_or_default();
+    config.hidden_line_languages = languages;
+    md.ext.insert(config);
+}
+
+/// Enable a Rust-Playground-style "Run" link on fences whose language is in
+/// `languages`, pointing at `base_url` (see [`PlaygroundConfig`]). Not
+/// called by default, so default rendering is unchanged.
+pub fn set_playground_config(md: &mut MarkdownIt, base_url: String, languages: Vec<String>) {
+    md.ext.insert(PlaygroundConfig { base_url, languages });
 }
 
 #[doc(hidden)]
 pub struct FenceScanner;
 
+/// Outcome of probing the current line for a fence opening: either a
+/// well-formed header, a line that isn't a fence at all, or a backtick
+/// fence whose info string illegally contains a backtick (a distinct case
+/// from "not a fence", since it's still worth a [`FenceDiagnostic`] even
+/// though the line falls through to be reparsed by another rule).
+enum FenceHeader<'a> {
+    Fence(char, usize, &'a str),
+    IllegalBacktickInfo,
+    NotAFence,
+}
+
 impl FenceScanner {
-    fn get_header<'a>(state: &'a mut BlockState) -> Option<(char, usize, &'a str)> {
+    fn get_header(state: &mut BlockState) -> FenceHeader<'_> {
         if state.line_indent(state.line) >= state.md.max_indent {
-            return None;
+            return FenceHeader::NotAFence;
         }
 
         let line = state.get_line(state.line);
         let mut chars = line.chars();
 
-        let marker = chars.next()?;
+        let Some(marker) = chars.next() else {
+            return FenceHeader::NotAFence;
+        };
         if marker != '~' && marker != '`' {
-            return None;
+            return FenceHeader::NotAFence;
         }
 
         // scan marker length
@@ -98,26 +491,61 @@ impl FenceScanner {
         }
 
         if len < 3 {
-            return None;
+            return FenceHeader::NotAFence;
         }
 
         let params = &line[len..];
 
         if marker == '`' && params.contains(marker) {
-            return None;
+            return FenceHeader::IllegalBacktickInfo;
         }
 
-        Some((marker, len, params))
+        FenceHeader::Fence(marker, len, params)
+    }
+
+    /// Record a [`FenceDiagnostic`] for a backtick fence whose info string
+    /// illegally contains a backtick, deduplicated per line since `check`
+    /// may probe the same line more than once.
+    fn record_illegal_backtick_info(state: &mut BlockState) {
+        let line = state.line;
+        let diagnostics = state.root_ext.get_or_insert_default::<FenceDiagnostics>();
+        if diagnostics
+            .0
+            .iter()
+            .any(|d| d.code == BACKTICK_IN_INFO_CODE && d.line_start == line)
+        {
+            return;
+        }
+        diagnostics.0.push(FenceDiagnostic {
+            code: BACKTICK_IN_INFO_CODE,
+            message: "a backtick code fence's info string cannot contain a backtick".to_string(),
+            line_start: line,
+            line_end: line + 1,
+        });
     }
 }
 
 impl BlockRule for FenceScanner {
     fn check(state: &mut BlockState) -> Option<()> {
-        Self::get_header(state).map(|_| ())
+        match Self::get_header(state) {
+            FenceHeader::Fence(..) => Some(()),
+            FenceHeader::IllegalBacktickInfo => {
+                Self::record_illegal_backtick_info(state);
+                None
+            }
+            FenceHeader::NotAFence => None,
+        }
     }
 
     fn run(state: &mut BlockState) -> Option<(Node, usize)> {
-        let (marker, len, params) = Self::get_header(state)?;
+        let (marker, len, params) = match Self::get_header(state) {
+            FenceHeader::Fence(marker, len, params) => (marker, len, params),
+            FenceHeader::IllegalBacktickInfo => {
+                Self::record_illegal_backtick_info(state);
+                return None;
+            }
+            FenceHeader::NotAFence => return None,
+        };
         let params = params.to_owned();
 
         let mut next_line = state.line;
@@ -176,23 +604,61 @@ impl BlockRule for FenceScanner {
             }
         }
 
+        if !have_end_marker {
+            let open_line = state.line;
+            let diagnostics = state.root_ext.get_or_insert_default::<FenceDiagnostics>();
+            diagnostics.0.push(FenceDiagnostic {
+                code: UNTERMINATED_FENCE_CODE,
+                message: "code fence was closed by end of document or parent block, not its own closing marker".to_string(),
+                line_start: open_line,
+                line_end: next_line,
+            });
+        }
+
         // If a fence has heading spaces, they should be removed from its inner block
         let indent = state.line_offsets[state.line].indent_nonspace;
         let (content, _) = state.get_lines(state.line + 1, next_line, indent as usize, true);
 
-        let lang_prefix = state
+        let config = state.md.ext.get::<FenceSettings>().cloned().unwrap_or_default();
+        let (lang_name, fence_attrs) = parse_fence_info(&unescape_all(&params));
+        let (display_content, full_content) = if config
+            .hidden_line_languages
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(&lang_name))
+        {
+            split_hidden_lines(&content)
+        } else {
+            (content.clone(), content)
+        };
+        let is_runnable = !fence_attrs
+            .classes
+            .iter()
+            .any(|c| c == "ignore" || c == "no_run");
+        let playground_url = state
             .md
             .ext
-            .get::<FenceSettings>()
-            .copied()
-            .unwrap_or_default()
-            .0;
+            .get::<PlaygroundConfig>()
+            .filter(|playground| {
+                is_runnable
+                    && playground
+                        .languages
+                        .iter()
+                        .any(|l| l.eq_ignore_ascii_case(&lang_name))
+            })
+            .map(|playground| playground_url(playground, &full_content));
+
         let node = Node::new(CodeFence {
             info: params,
             marker,
             marker_len: len,
-            content,
-            lang_prefix,
+            display_content,
+            full_content,
+            lang_prefix: config.lang_prefix,
+            lang_name,
+            fence_attrs,
+            highlight_mode: config.highlight_mode,
+            theme_name: config.theme_name,
+            playground_url,
         });
         Some((
             node,
@@ -0,0 +1,77 @@
+//! Generic, non-fatal parse diagnostics collected across plugins.
+//!
+//! Several rules hit situations that aren't fatal parse errors but also
+//! aren't quite right: an out-of-range `【index】` citation, a `[^label]`
+//! footnote reference with no definition, and so on. Today those rules
+//! just fall back to silently rendering plain text. Rather than lose that
+//! information, each one also pushes a [`Diagnostic`] into [`Diagnostics`]
+//! on the document root (via `root_ext`, the same way
+//! [`FenceDiagnostics`](crate::plugins::cmark::block::fence::FenceDiagnostics)
+//! does for malformed code fences), so
+//! [`MDParser::render_with_diagnostics`](crate::MDParser::render_with_diagnostics)
+//! can hand callers linting user-authored markdown the full list instead of
+//! just the rendered HTML.
+use crate::mdparser::extset::RootExt;
+use pyo3::prelude::*;
+
+/// An out-of-range `【index】` citation.
+pub const OUT_OF_RANGE_CITATION: &str = "out-of-range-citation";
+/// A `[^label]` footnote reference with no matching `[^label]: ...`
+/// definition.
+pub const DANGLING_FOOTNOTE_REFERENCE: &str = "dangling-footnote-reference";
+/// A second `[^label]: ...` definition for a label that already has one.
+pub const DUPLICATE_FOOTNOTE_DEFINITION: &str = "duplicate-footnote-definition";
+/// A `[text][label]`/`[label]` reference with no matching `[label]: url`
+/// definition.
+pub const UNDEFINED_REFERENCE: &str = "undefined-reference";
+
+/// A single non-fatal parse problem: `code` is one of the constants above,
+/// `span_start`/`span_end` is the byte range in the original source that
+/// triggered it (pair with a byte-offset-to-line/column index to report a
+/// human-readable position), and `message` is a one-line description.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    #[pyo3(get)]
+    pub code: String,
+    #[pyo3(get)]
+    pub span_start: usize,
+    #[pyo3(get)]
+    pub span_end: usize,
+    #[pyo3(get)]
+    pub message: String,
+}
+
+#[pymethods]
+impl Diagnostic {
+    #[new]
+    fn new(code: String, span_start: usize, span_end: usize, message: String) -> Self {
+        Self {
+            code,
+            span_start,
+            span_end,
+            message,
+        }
+    }
+}
+
+impl Diagnostic {
+    pub fn new_internal(
+        code: &'static str,
+        span: std::ops::Range<usize>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            code: code.to_string(),
+            span_start: span.start,
+            span_end: span.end,
+            message: message.into(),
+        }
+    }
+}
+
+/// Every [`Diagnostic`] pushed while parsing the document, stored on the
+/// root node.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+impl RootExt for Diagnostics {}
@@ -41,8 +41,14 @@
 //! // ]
 // ```
 
+use std::collections::HashMap;
+
+use crate::mdparser::block::builtin::BlockParserRule;
 use crate::mdparser::block::{BlockRule, BlockState};
-use crate::mdparser::core::Root;
+use crate::mdparser::core::{CoreRule, Root};
+use crate::mdparser::inline::builtin::InlineParserRule;
+use crate::mdparser::extset::RootExt;
+use crate::mdparser::inline::{InlineRule, InlineState};
 use crate::{MarkdownIt, Node, NodeValue, Renderer};
 
 #[derive(Debug)]
@@ -57,10 +63,96 @@ impl NodeValue for FrontMatter {
     }
 }
 
+/// Variables parsed out of the document's front-matter block, keyed by their
+/// YAML key, so `{{ name }}` placeholders elsewhere in the document can be
+/// resolved against them.
+#[derive(Debug, Default)]
+pub struct FrontMatterVars(pub HashMap<String, String>);
+impl RootExt for FrontMatterVars {}
+
+/// Parse the (flat, scalar-valued) `key: value` lines of a front-matter
+/// block into a variable map. This is intentionally not a full YAML parser:
+/// it only understands the simple `key: value` shape front matter is
+/// typically used for, stripping a single layer of surrounding quotes from
+/// the value.
+fn parse_front_matter_vars(content: &str) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            continue;
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .unwrap_or(value);
+        vars.insert(key.to_string(), value.to_string());
+    }
+    vars
+}
+
 /// Add the front-matter extension to the markdown parser
 pub fn add(md: &mut MarkdownIt) {
     // insert this rule into block subparser
     md.block.add_rule::<FrontMatterBlockScanner>().before_all();
+    md.add_rule::<FrontMatterVarsRule>()
+        .after::<BlockParserRule>()
+        .before::<InlineParserRule>();
+    md.inline.add_rule::<TemplateVarScanner>();
+}
+
+/// A [CoreRule] that parses the document's [`FrontMatter`] block (if any)
+/// into a [`FrontMatterVars`] map, run before inline parsing so
+/// [`TemplateVarScanner`] can resolve `{{ name }}` placeholders against it.
+struct FrontMatterVarsRule;
+impl CoreRule for FrontMatterVarsRule {
+    fn run(root: &mut Node, _: &MarkdownIt) {
+        let mut vars = HashMap::new();
+        root.walk(|node, _| {
+            if let Some(front_matter) = node.cast::<FrontMatter>() {
+                vars.extend(parse_front_matter_vars(&front_matter.content));
+            }
+        });
+
+        let data = root.cast_mut::<Root>().unwrap();
+        data.ext.insert(FrontMatterVars(vars));
+    }
+}
+
+/// A `{{ name }}` placeholder, resolved against [`FrontMatterVars`] at
+/// render time.
+#[derive(Debug)]
+pub struct TemplateVar {
+    pub value: String,
+}
+
+impl NodeValue for TemplateVar {
+    fn render(&self, _node: &Node, fmt: &mut dyn Renderer) {
+        fmt.text(&self.value);
+    }
+}
+
+struct TemplateVarScanner;
+impl InlineRule for TemplateVarScanner {
+    const MARKER: char = '{';
+
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let input = &state.src[state.pos..state.pos_max];
+        let rest = input.strip_prefix("{{")?;
+        let end = rest.find("}}")?;
+        let name = rest[..end].trim();
+
+        // unresolved names are left verbatim rather than dropped
+        let vars = state.root_ext.get::<FrontMatterVars>()?;
+        let value = vars.0.get(name)?.clone();
+
+        Some((Node::new(TemplateVar { value }), end + 4))
+    }
 }
 
 /// An extension for the block subparser.
@@ -119,10 +211,19 @@ mod tests {
         let parser = &mut crate::MarkdownIt::new();
         add(parser);
         let node = parser.parse("---\nfoo: bar\n---\nhallo\n");
-        // println!("{:#?}", ast.children.first());
         assert!(node.children.first().unwrap().is::<FrontMatter>());
 
         let text = node.render();
         assert_eq!(text, "hallo\n")
     }
+
+    #[test]
+    fn substitutes_known_variables_and_leaves_unknown_ones_verbatim() {
+        let parser = &mut crate::MarkdownIt::new();
+        add(parser);
+        let text = parser
+            .parse("---\nname: world\n---\nHello {{ name }}, {{ missing }}!\n")
+            .render();
+        assert_eq!(text, "Hello world, {{ missing }}!\n")
+    }
 }
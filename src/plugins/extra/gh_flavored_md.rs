@@ -7,10 +7,8 @@
 //! assert_eq!(root.render(), "<p><a href=\"https://github.github.com/gfm\">https://github.github.com/gfm</a></p>\n");
 //! ```
 use crate::mdparser::inline::builtin::InlineParserRule;
-use crate::plugins::html::html_block::HtmlBlock;
-use crate::plugins::html::html_inline::HtmlInline;
-use crate::{mdparser::core::CoreRule, MarkdownIt, Node};
-use regex::Regex;
+use crate::plugins::extra::tagfilter::TagFilterRule;
+use crate::MarkdownIt;
 
 pub const GITHUB_PLUGIN_NAMES: [&str; 22] = [
     "newline",
@@ -43,7 +41,7 @@ pub fn add(md: &mut MarkdownIt) {
     crate::plugins::extra::tables::add(md);
     crate::plugins::extra::strikethrough::add(md);
     crate::plugins::html::add(md);
-    md.add_rule::<TagFilter>().after::<InlineParserRule>();
+    md.add_rule::<TagFilterRule>().after::<InlineParserRule>();
     crate::plugins::extra::tasklist::add_disabled(md);
     crate::plugins::autolinks::add(md);
 }
@@ -53,22 +51,3 @@ pub fn add_with_anchors(md: &mut MarkdownIt) {
     add(md);
     crate::plugins::extra::heading_anchors::add(md);
 }
-
-/// Implement the Disallowed Raw HTML (tagfilter) rule
-struct TagFilter;
-impl CoreRule for TagFilter {
-    fn run(root: &mut Node, _md: &MarkdownIt) {
-        let regex = Regex::new(
-            r#"<(?i)(iframe|noembed|noframes|plaintext|script|style|title|textarea|xmp)"#,
-        )
-        .unwrap();
-        root.walk_mut(|node, _| {
-            if let Some(value) = node.cast_mut::<HtmlBlock>() {
-                value.content = regex.replace_all(&value.content, "&lt;$1").to_string();
-            }
-            if let Some(value) = node.cast_mut::<HtmlInline>() {
-                value.content = regex.replace_all(&value.content, "&lt;$1").to_string();
-            }
-        });
-    }
-}
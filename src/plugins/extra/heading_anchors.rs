@@ -0,0 +1,60 @@
+//! Assigns a unique, GitHub-style `id` to every heading so it can be linked
+//! to directly, e.g. `[see below](#installation)`.
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::extra::heading_anchors::add(parser);
+//! let html = parser.parse("# Hello World\n\n# Hello World").render();
+//! assert!(html.contains(r#"id="hello-world""#));
+//! assert!(html.contains(r#"id="hello-world-1""#));
+//! ```
+use crate::mdparser::core::CoreRule;
+use crate::plugins::cmark::block::heading::ATXHeading;
+use crate::plugins::cmark::block::lheading::SetextHeader;
+use crate::plugins::extra::github_slugger::Slugger;
+use crate::{MarkdownIt, Node, NodeValue, Renderer};
+
+/// Add the heading-anchor plugin to the parser.
+pub fn add(md: &mut MarkdownIt) {
+    md.add_rule::<HeadingAnchorsRule>();
+}
+
+/// Anchor injected as the leading child of a slugged heading, so the
+/// heading itself can be linked to with `<a href="#slug">`.
+#[derive(Debug)]
+pub struct HeaderAnchor {
+    pub slug: String,
+}
+
+impl NodeValue for HeaderAnchor {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let mut attrs = node.attrs.clone();
+        attrs.push(("class", "header-anchor".into()));
+        attrs.push(("href", format!("#{}", self.slug)));
+        fmt.open("a", &attrs);
+        fmt.close("a");
+    }
+}
+
+/// A [CoreRule] that walks the finished AST, slugs each heading's plain
+/// text (via [`Node::collect_text`], so markup itself is never slugged),
+/// and pushes `("id", slug)` onto the heading's attrs.
+struct HeadingAnchorsRule;
+
+impl CoreRule for HeadingAnchorsRule {
+    fn run(root: &mut Node, _: &MarkdownIt) {
+        // one slugger per document, so repeated headings become `foo`, `foo-1`, `foo-2`
+        let mut slugger = Slugger::default();
+
+        root.walk_mut(|node, _| {
+            if !(node.is::<ATXHeading>() || node.is::<SetextHeader>()) {
+                return;
+            }
+
+            let slug = slugger.slug(&node.collect_text());
+            node.attrs.push(("id", slug.clone()));
+            node.children.insert(0, Node::new(HeaderAnchor { slug }));
+        });
+    }
+}
@@ -0,0 +1,218 @@
+//! Configurable rewriting of link "shorthands" into full URLs, inspired by
+//! [markdown-linkify](https://github.com/srsholmes/markdown-linkify).
+//!
+//! A user registers a set of [`LinkShorthand`] transformers, each a
+//! compiled pattern plus a URL (and optional title) template. Any
+//! `[TAG]`-style shortcut reference link (one with no matching link
+//! reference definition, so it would otherwise render as literal bracketed
+//! text) or `[title](tag)` inline link whose tag/href matches a
+//! transformer's pattern in full is rewritten to the expanded href, with a
+//! title/link text derived from the template when the source left it
+//! empty. Transformers are tried in registration order; the first match
+//! wins, and a tag that matches none of them is left untouched.
+//!
+//! ```rust
+//! use quickmark::plugins::extra::link_shorthands::LinkShorthand;
+//!
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::extra::link_shorthands::add(
+//!     parser,
+//!     vec![LinkShorthand::new(r"^PS-\d+$", "https://company.jira.com/issues/$0").unwrap()],
+//! );
+//! let html = parser.parse("See [PS-128] for details.").render();
+//! assert!(html.contains(r#"<a href="https://company.jira.com/issues/PS-128">"#));
+//! ```
+use regex::Regex;
+
+use crate::mdparser::core::CoreRule;
+use crate::mdparser::extset::MarkdownItExt;
+use crate::plugins::cmark::inline::autolink::Autolink;
+use crate::plugins::cmark::inline::backticks::CodeInline;
+use crate::plugins::cmark::inline::link::Link;
+use crate::mdparser::inline::builtin::skip_text::Text;
+use crate::{MarkdownIt, Node};
+
+/// One shorthand transformer: `pattern` is matched against the tag/href in
+/// full (an anchored search, not a substring one), and on a match
+/// `url_template`/`title_template` have `$0`, `$1`, ... substituted in the
+/// same way [`regex::Captures::expand`] does, where `$0` is the whole
+/// matched tag.
+#[derive(Debug, Clone)]
+pub struct LinkShorthand {
+    pattern: Regex,
+    url_template: String,
+    title_template: Option<String>,
+}
+
+impl LinkShorthand {
+    /// Build a transformer from a regex `pattern` and a `url_template`
+    /// (e.g. `"https://company.jira.com/issues/$0"`). Fails if `pattern`
+    /// doesn't compile.
+    pub fn new(pattern: &str, url_template: impl Into<String>) -> Result<Self, regex::Error> {
+        Ok(Self {
+            pattern: Regex::new(pattern)?,
+            url_template: url_template.into(),
+            title_template: None,
+        })
+    }
+
+    /// Also derive a link title/text from `title_template` when the source
+    /// didn't supply one.
+    pub fn with_title_template(mut self, title_template: impl Into<String>) -> Self {
+        self.title_template = Some(title_template.into());
+        self
+    }
+
+    /// Expand this transformer against `tag` if it matches in full,
+    /// returning the expanded `(url, title)`.
+    fn expand(&self, tag: &str) -> Option<(String, Option<String>)> {
+        let caps = self.pattern.captures(tag)?;
+        if caps.get(0)?.as_str() != tag {
+            return None;
+        }
+
+        let mut url = String::new();
+        caps.expand(&self.url_template, &mut url);
+
+        let title = self.title_template.as_ref().map(|template| {
+            let mut title = String::new();
+            caps.expand(template, &mut title);
+            title
+        });
+
+        Some((url, title))
+    }
+}
+
+/// The registered [`LinkShorthand`] transformers for a parser, tried in
+/// registration order.
+#[derive(Debug, Clone, Default)]
+struct LinkShorthandSet(Vec<LinkShorthand>);
+impl MarkdownItExt for LinkShorthandSet {}
+
+impl LinkShorthandSet {
+    fn expand(&self, tag: &str) -> Option<(String, Option<String>)> {
+        self.0.iter().find_map(|transformer| transformer.expand(tag))
+    }
+}
+
+/// Add the link-shorthand plugin to the parser with `transformers`, tried
+/// in the order given.
+pub fn add(md: &mut MarkdownIt, transformers: Vec<LinkShorthand>) {
+    md.ext.insert(LinkShorthandSet(transformers));
+    md.add_rule::<LinkShorthandRule>();
+}
+
+/// A [CoreRule] that runs after inline parsing, rewriting already-resolved
+/// [`Link`] hrefs and literal `[TAG]` bracket text that match a registered
+/// [`LinkShorthand`]. Operates on raw `children` vectors (rather than the
+/// generic `walk_mut`) since a literal bracket shorthand needs to split one
+/// [`Text`] leaf into `Text`/`Link`/`Text` siblings.
+struct LinkShorthandRule;
+impl CoreRule for LinkShorthandRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        let Some(shorthands) = md.ext.get::<LinkShorthandSet>() else {
+            return;
+        };
+        if shorthands.0.is_empty() {
+            return;
+        }
+        rewrite_children(&mut root.children, shorthands);
+    }
+}
+
+fn rewrite_children(children: &mut Vec<Node>, shorthands: &LinkShorthandSet) {
+    let mut i = 0;
+    while i < children.len() {
+        // a bracket shorthand's tag is never itself inside a link, autolink,
+        // or code span, so don't descend into (or rewrite the href of) one
+        // we've already expanded
+        if let Some(link) = children[i].cast_mut::<Link>() {
+            if let Some((url, title)) = shorthands.expand(&link.url) {
+                link.url = url;
+                if link.title.is_none() {
+                    link.title = title.clone();
+                }
+                if children[i].collect_text().trim().is_empty() {
+                    let text = title.unwrap_or_else(|| link.url.clone());
+                    children[i].children = vec![Node::new(Text { content: text })];
+                }
+            }
+            i += 1;
+            continue;
+        }
+        if children[i].is::<Autolink>() || children[i].is::<CodeInline>() {
+            i += 1;
+            continue;
+        }
+
+        rewrite_children(&mut children[i].children, shorthands);
+
+        if let Some(text) = children[i].cast::<Text>() {
+            if let Some(expanded) = expand_bracket_shorthands(&text.content, shorthands) {
+                let len = expanded.len();
+                children.splice(i..i + 1, expanded);
+                i += len;
+                continue;
+            }
+        }
+
+        i += 1;
+    }
+}
+
+/// Split `content` on every standalone `[TAG]` (not immediately followed by
+/// `(` or `[`, which would make it a regular/reference-style link instead
+/// of a shortcut reference) whose tag matches a registered shorthand, into
+/// alternating `Text`/`Link` nodes. Returns `None` when nothing matched, so
+/// the caller can leave the original `Text` node untouched.
+fn expand_bracket_shorthands(content: &str, shorthands: &LinkShorthandSet) -> Option<Vec<Node>> {
+    let mut out = Vec::new();
+    let mut last_end = 0;
+    let mut matched = false;
+
+    let bytes = content.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'[' {
+            i += 1;
+            continue;
+        }
+        let Some(rel_end) = content[i + 1..].find(']') else {
+            i += 1;
+            continue;
+        };
+        let close = i + 1 + rel_end;
+        let tag = &content[i + 1..close];
+        let next = content[close + 1..].chars().next();
+        if matches!(next, Some('(') | Some('[')) || tag.is_empty() {
+            i = close + 1;
+            continue;
+        }
+
+        if let Some((url, title)) = shorthands.expand(tag) {
+            if last_end < i {
+                out.push(Node::new(Text { content: content[last_end..i].to_string() }));
+            }
+            let mut link = Node::new(Link {
+                url,
+                title: title.clone(),
+            });
+            link.children = vec![Node::new(Text { content: title.unwrap_or_else(|| tag.to_string()) })];
+            out.push(link);
+            matched = true;
+            last_end = close + 1;
+        }
+
+        i = close + 1;
+    }
+
+    if !matched {
+        return None;
+    }
+    if last_end < content.len() {
+        out.push(Node::new(Text { content: content[last_end..].to_string() }));
+    }
+    Some(out)
+}
@@ -0,0 +1,134 @@
+//! A [markdown_it] plugin implementing inline (`$...$`) and block (`$$`
+//! fenced) math, mirroring [markdown-rs's math
+//! extension](https://github.com/wooorm/markdown-rs).
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::extra::math::add(parser);
+//! let html = parser.parse("Energy is $E = mc^2$.").render();
+//! assert_eq!(html.trim(), r#"<p>Energy is <span class="math inline">E = mc^2</span>.</p>"#);
+//! ```
+use crate::mdparser::block::{BlockRule, BlockState};
+use crate::mdparser::inline::{InlineRule, InlineState};
+use crate::{MarkdownIt, Node, NodeValue, Renderer};
+
+/// AST node for inline math: `$...$`.
+#[derive(Debug)]
+pub struct MathInline {
+    pub content: String,
+}
+
+impl NodeValue for MathInline {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let attrs = node.attrs.clone();
+        fmt.open("span", &attrs_with_class(attrs, "math inline"));
+        fmt.text(&self.content);
+        fmt.close("span");
+    }
+}
+
+/// AST node for block math: `$$` fenced on its own lines.
+#[derive(Debug)]
+pub struct MathBlock {
+    pub content: String,
+}
+
+impl NodeValue for MathBlock {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let attrs = node.attrs.clone();
+        fmt.open("div", &attrs_with_class(attrs, "math display"));
+        fmt.text(&self.content);
+        fmt.close("div");
+        fmt.cr();
+    }
+}
+
+fn attrs_with_class(mut attrs: Vec<(&'static str, String)>, class: &str) -> Vec<(&'static str, String)> {
+    attrs.push(("class", class.to_string()));
+    attrs
+}
+
+/// Add the math extension to the parser.
+pub fn add(md: &mut MarkdownIt) {
+    md.inline.add_rule::<MathInlineScanner>();
+    md.block.add_rule::<MathBlockScanner>();
+}
+
+struct MathInlineScanner;
+impl InlineRule for MathInlineScanner {
+    const MARKER: char = '$';
+
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let input = &state.src[state.pos..state.pos_max];
+        let rest = input.strip_prefix('$')?;
+        if rest.starts_with('$') {
+            // `$$` is reserved for block math; don't steal it here
+            return None;
+        }
+
+        let close_rel = find_unescaped_dollar(rest)?;
+        let content = &rest[..close_rel];
+        if content.is_empty() || content.starts_with(char::is_whitespace) || content.ends_with(char::is_whitespace) {
+            return None;
+        }
+
+        let matched_len = 1 + close_rel + 1;
+
+        // reject currency-looking `$5 and $6`: a digit immediately inside
+        // the opening delimiter *and* a digit immediately after the closing
+        // one
+        let opens_on_digit = content.starts_with(|c: char| c.is_ascii_digit());
+        let digit_follows = input[matched_len..].starts_with(|c: char| c.is_ascii_digit());
+        if opens_on_digit && digit_follows {
+            return None;
+        }
+
+        Some((
+            Node::new(MathInline {
+                content: content.to_string(),
+            }),
+            matched_len,
+        ))
+    }
+}
+
+/// Find the offset of the next `$` in `s` that isn't escaped with a
+/// preceding backslash, or `None` if there isn't one.
+fn find_unescaped_dollar(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'$' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+struct MathBlockScanner;
+impl BlockRule for MathBlockScanner {
+    fn run(state: &mut BlockState) -> Option<(Node, usize)> {
+        let first_line = state.get_line(state.line);
+        if first_line.trim() != "$$" {
+            return None;
+        }
+
+        let mut next_line = state.line;
+        loop {
+            next_line += 1;
+            if next_line >= state.line_max {
+                return None;
+            }
+            if state.get_line(next_line).trim() == "$$" {
+                break;
+            }
+        }
+
+        let (content, _) = state.get_lines(state.line + 1, next_line, 0, false);
+
+        Some((Node::new(MathBlock { content }), next_line + 1 - state.line))
+    }
+}
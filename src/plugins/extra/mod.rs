@@ -24,9 +24,18 @@ pub mod github_slugger;
 pub mod heading_anchors;
 #[cfg(feature = "linkify")]
 pub mod linkify;
+pub mod link_shorthands;
+#[cfg(feature = "math")]
+pub mod math;
+pub mod shortlinks;
 pub mod smartquotes;
 pub mod strikethrough;
+#[cfg(feature = "subscript")]
+pub mod subscript;
+#[cfg(feature = "superscript")]
+pub mod superscript;
 pub mod tables;
+pub mod tagfilter;
 pub mod tasklist;
 pub mod typographer;
 
@@ -37,7 +46,14 @@ pub fn add(md: &mut MarkdownIt) {
     beautify_links::add(md);
     #[cfg(feature = "linkify")]
     linkify::add(md);
+    #[cfg(feature = "math")]
+    math::add(md);
+    #[cfg(feature = "superscript")]
+    superscript::add(md);
+    #[cfg(feature = "subscript")]
+    subscript::add(md);
     tables::add(md);
+    tagfilter::add(md);
     typographer::add(md);
     smartquotes::add(md);
 }
@@ -0,0 +1,234 @@
+//! Inline expansion of internal "go-link"-style shortlinks, e.g. `b/123` or
+//! `cl/345`, into full URLs.
+//!
+//! A [`ShortlinkSet`] maps a registered prefix (`b`, `cl`, ...) to a URL
+//! template containing a `{}` placeholder for the identifier. A bare token
+//! of the form `prefix/identifier` in running text, at a word boundary and
+//! with a registered prefix, is rewritten into a normal [`Link`] node. The
+//! identifier must match the prefix's identifier pattern (`[0-9]+` by
+//! default). Shortlinks are never recognized inside existing links,
+//! autolinks, or code spans.
+//!
+//! ```rust
+//! use quickmark::plugins::extra::shortlinks::ShortlinkSet;
+//!
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! let mut shortlinks = ShortlinkSet::new();
+//! shortlinks.register("b", "https://bugs.example.com/{}");
+//! quickmark::plugins::extra::shortlinks::add_with_shortlinks(parser, shortlinks);
+//! let html = parser.parse("see b/123 for details").render();
+//! assert!(html.contains(r#"<a href="https://bugs.example.com/123">b/123</a>"#));
+//! ```
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::mdparser::core::CoreRule;
+use crate::mdparser::extset::MarkdownItExt;
+use crate::mdparser::inline::builtin::skip_text::Text;
+use crate::plugins::cmark::inline::autolink::Autolink;
+use crate::plugins::cmark::inline::backticks::CodeInline;
+use crate::plugins::cmark::inline::link::Link;
+use crate::{MarkdownIt, Node};
+
+const DEFAULT_IDENTIFIER_PATTERN: &str = "[0-9]+";
+
+/// [`DEFAULT_IDENTIFIER_PATTERN`], compiled once. [`ShortlinkSet::register`]
+/// (and so [`ShortlinkSet::defaults`]) compiles the pattern string itself
+/// rather than reusing this.
+static DEFAULT_IDENTIFIER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(DEFAULT_IDENTIFIER_PATTERN).unwrap());
+
+/// One registered shortlink prefix: `url_template` has its `{}`
+/// placeholder replaced with the matched identifier, which must match
+/// `identifier` in full.
+struct Shortlink {
+    url_template: String,
+    identifier: Regex,
+}
+
+/// A registry of shortlink prefixes, mapping each to a URL template and an
+/// identifier pattern.
+#[derive(Default)]
+pub struct ShortlinkSet {
+    prefixes: Vec<(String, Shortlink)>,
+}
+
+impl MarkdownItExt for ShortlinkSet {}
+
+impl ShortlinkSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in shortlink set: `b/123` and `cl/345`, both with the
+    /// default `[0-9]+` identifier pattern.
+    pub fn defaults() -> Self {
+        let mut set = Self::new();
+        set.register("b", "https://b.corp.example.com/{}");
+        set.register("cl", "https://cl.corp.example.com/{}");
+        set
+    }
+
+    /// Register `prefix` with a `url_template` containing a `{}`
+    /// placeholder, using the default `[0-9]+` identifier pattern.
+    pub fn register(&mut self, prefix: &str, url_template: &str) {
+        self.register_with_identifier(prefix, url_template, DEFAULT_IDENTIFIER_PATTERN);
+    }
+
+    /// Register `prefix` with a `url_template` and a custom `identifier`
+    /// pattern the part after `prefix/` must match in full.
+    pub fn register_with_identifier(&mut self, prefix: &str, url_template: &str, identifier: &str) {
+        self.prefixes.push((
+            prefix.to_string(),
+            Shortlink {
+                url_template: url_template.to_string(),
+                identifier: Regex::new(identifier).unwrap(),
+            },
+        ));
+    }
+
+    fn expand(&self, prefix: &str, identifier: &str) -> Option<String> {
+        let (_, shortlink) = self.prefixes.iter().find(|(p, _)| p == prefix)?;
+        if !matches_fully(&shortlink.identifier, identifier) {
+            return None;
+        }
+        Some(shortlink.url_template.replace("{}", identifier))
+    }
+}
+
+fn matches_fully(pattern: &Regex, text: &str) -> bool {
+    pattern.find(text).is_some_and(|m| m.start() == 0 && m.end() == text.len())
+}
+
+/// Add the shortlink plugin to the parser, recognizing [`ShortlinkSet::defaults`]'s
+/// built-in `b/` and `cl/` prefixes.
+pub fn add(md: &mut MarkdownIt) {
+    add_with_shortlinks(md, ShortlinkSet::defaults());
+}
+
+/// Add the shortlink plugin to the parser with a custom `shortlinks` set.
+pub fn add_with_shortlinks(md: &mut MarkdownIt, shortlinks: ShortlinkSet) {
+    md.ext.insert(shortlinks);
+    md.add_rule::<ShortlinkRule>();
+}
+
+/// A [CoreRule] that runs after inline parsing, splitting `Text` leaves on
+/// any `prefix/identifier` token that names a registered shortlink. Skips
+/// [`Link`], [`Autolink`], and [`CodeInline`] subtrees so shortlinks never
+/// fire inside existing links, autolinks, or code spans.
+struct ShortlinkRule;
+impl CoreRule for ShortlinkRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        let Some(shortlinks) = md.ext.get::<ShortlinkSet>() else {
+            return;
+        };
+        if shortlinks.prefixes.is_empty() {
+            return;
+        }
+        rewrite_children(&mut root.children, shortlinks);
+    }
+}
+
+fn rewrite_children(children: &mut Vec<Node>, shortlinks: &ShortlinkSet) {
+    let mut i = 0;
+    while i < children.len() {
+        if children[i].is::<Link>() || children[i].is::<Autolink>() || children[i].is::<CodeInline>() {
+            i += 1;
+            continue;
+        }
+
+        rewrite_children(&mut children[i].children, shortlinks);
+
+        if let Some(text) = children[i].cast::<Text>() {
+            if let Some(expanded) = expand_shortlinks(&text.content, shortlinks) {
+                let len = expanded.len();
+                children.splice(i..i + 1, expanded);
+                i += len;
+                continue;
+            }
+        }
+
+        i += 1;
+    }
+}
+
+fn is_word_boundary(c: Option<char>) -> bool {
+    !matches!(c, Some(c) if c.is_alphanumeric() || c == '_' || c == '/')
+}
+
+/// Split `content` on every `prefix/identifier` token that names a
+/// registered shortlink and sits at a word boundary on both sides, into
+/// alternating `Text`/`Link` nodes. Returns `None` when nothing matched.
+fn expand_shortlinks(content: &str, shortlinks: &ShortlinkSet) -> Option<Vec<Node>> {
+    let mut out = Vec::new();
+    let mut last_end = 0;
+    let mut matched = false;
+
+    let chars: Vec<(usize, char)> = content.char_indices().collect();
+    let mut idx = 0;
+    while idx < chars.len() {
+        let (start, _) = chars[idx];
+        let before = if idx == 0 { None } else { Some(chars[idx - 1].1) };
+        if !is_word_boundary(before) {
+            idx += 1;
+            continue;
+        }
+
+        let Some(slash_pos) = chars[idx..].iter().position(|&(_, c)| c == '/') else {
+            idx += 1;
+            continue;
+        };
+        let slash_idx = idx + slash_pos;
+        let prefix_end = chars[slash_idx].0;
+        let prefix = &content[start..prefix_end];
+        if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphabetic()) {
+            idx += 1;
+            continue;
+        }
+
+        let ident_start_idx = slash_idx + 1;
+        let mut end_idx = ident_start_idx;
+        while end_idx < chars.len() && is_identifier_char(chars[end_idx].1) {
+            end_idx += 1;
+        }
+        if end_idx == ident_start_idx {
+            idx += 1;
+            continue;
+        }
+        let ident_start = chars[ident_start_idx].0;
+        let token_end = if end_idx < chars.len() { chars[end_idx].0 } else { content.len() };
+        let identifier = &content[ident_start..token_end];
+        let after = if end_idx < chars.len() { Some(chars[end_idx].1) } else { None };
+
+        if is_word_boundary(after) {
+            if let Some(url) = shortlinks.expand(prefix, identifier) {
+                if last_end < start {
+                    out.push(Node::new(Text { content: content[last_end..start].to_string() }));
+                }
+                let token = &content[start..token_end];
+                let mut link = Node::new(Link { url, title: None });
+                link.children = vec![Node::new(Text { content: token.to_string() })];
+                out.push(link);
+                matched = true;
+                last_end = token_end;
+                idx = end_idx;
+                continue;
+            }
+        }
+
+        idx += 1;
+    }
+
+    if !matched {
+        return None;
+    }
+    if last_end < content.len() {
+        out.push(Node::new(Text { content: content[last_end..].to_string() }));
+    }
+    Some(out)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
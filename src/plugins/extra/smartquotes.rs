@@ -0,0 +1,112 @@
+//! Converts straight quotes (`"`, `'`) into curly "smart" quotes
+//! (`“”`/`‘’`), picking the opening vs. closing form from whichever
+//! character precedes the quote.
+//!
+//! Like [`typographer`](super::typographer), uses
+//! [`Node::walk_mut_enter_exit`](crate::Node::walk_mut_enter_exit) with a
+//! `link_level` accumulator so link text and URLs are left untouched.
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::extra::smartquotes::add(parser);
+//! let html = parser.parse(r#"She said "hi" to [the "team"](http://example.com)"#).render();
+//! assert!(html.contains("\u{201c}hi\u{201d}"));
+//! assert!(html.contains("the &quot;team&quot;</a>"));
+//! ```
+use crate::mdparser::core::CoreRule;
+use crate::mdparser::inline::builtin::skip_text::Text;
+use crate::plugins::cmark::inline::autolink::Autolink;
+use crate::plugins::cmark::inline::link::Link;
+use crate::{MarkdownIt, Node};
+
+/// Characters after which a quote is read as "opening" rather than
+/// "closing".
+fn opens_quote(prev: Option<char>) -> bool {
+    match prev {
+        None => true,
+        Some(c) => c.is_whitespace() || "([{-\u{2014}\u{2013}".contains(c),
+    }
+}
+
+/// Replace straight quotes in `s` with their curly equivalent, threading
+/// `prev_char` (the last character written, across calls) so the
+/// opening/closing choice is correct at text-node boundaries too.
+fn smarten(s: &str, prev_char: &mut Option<char>) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        let replacement = match c {
+            '"' if opens_quote(*prev_char) => '\u{201C}',
+            '"' => '\u{201D}',
+            '\'' if opens_quote(*prev_char) => '\u{2018}',
+            '\'' => '\u{2019}',
+            other => other,
+        };
+        out.push(replacement);
+        *prev_char = Some(c);
+    }
+    out
+}
+
+/// Add the smartquotes extension to the parser.
+pub fn add(md: &mut MarkdownIt) {
+    md.add_rule::<SmartquotesRule>();
+}
+
+#[derive(Default)]
+struct SmartquotesState {
+    link_level: u32,
+    prev_char: Option<char>,
+}
+
+struct SmartquotesRule;
+impl CoreRule for SmartquotesRule {
+    fn run(root: &mut Node, _md: &MarkdownIt) {
+        let mut state = SmartquotesState::default();
+        root.walk_mut_enter_exit(
+            &mut state,
+            |node, _depth, state| {
+                if node.is::<Link>() || node.is::<Autolink>() {
+                    state.link_level += 1;
+                    return;
+                }
+                if state.link_level > 0 {
+                    return;
+                }
+                if let Some(text) = node.cast_mut::<Text>() {
+                    text.content = smarten(&text.content, &mut state.prev_char);
+                }
+            },
+            |node, _depth, state| {
+                if node.is::<Link>() || node.is::<Autolink>() {
+                    state.link_level -= 1;
+                }
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn curls_straight_quotes() {
+        let parser = &mut crate::MarkdownIt::new();
+        crate::plugins::cmark::add(parser);
+        super::add(parser);
+        let html = parser.parse(r#"She said "hi" to 'them'"#).render();
+        assert!(html.contains("\u{201C}hi\u{201D}"));
+        assert!(html.contains("\u{2018}them\u{2019}"));
+    }
+
+    #[test]
+    fn skips_quote_replacement_inside_links() {
+        let parser = &mut crate::MarkdownIt::new();
+        crate::plugins::cmark::add(parser);
+        super::add(parser);
+        let html = parser
+            .parse(r#"She said "hi" to [the "team"](http://example.com)"#)
+            .render();
+        assert!(html.contains("\u{201C}hi\u{201D}"));
+        assert!(html.contains("the &quot;team&quot;</a>"));
+    }
+}
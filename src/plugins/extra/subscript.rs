@@ -0,0 +1,65 @@
+//! A [markdown_it] plugin for subscript text: `~text~` renders as
+//! `<sub>text</sub>`. Yields to [`strikethrough`](super::strikethrough)'s
+//! `~~text~~` syntax by declining any run starting with a second `~`.
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::extra::subscript::add(parser);
+//! let html = parser.parse("H~2~O").render();
+//! assert_eq!(html.trim(), "<p>H<sub>2</sub>O</p>");
+//! ```
+use crate::mdparser::inline::{InlineRule, InlineState};
+use crate::plugins::extra::superscript::find_unescaped;
+use crate::{MarkdownIt, Node, NodeValue, Renderer};
+
+/// AST node for subscript text: `~text~`.
+#[derive(Debug)]
+pub struct Subscript;
+
+impl NodeValue for Subscript {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("sub", &node.attrs);
+        fmt.contents(&node.children);
+        fmt.close("sub");
+    }
+}
+
+/// Add the subscript extension to the parser.
+pub fn add(md: &mut MarkdownIt) {
+    md.inline.add_rule::<SubscriptScanner>();
+}
+
+struct SubscriptScanner;
+impl InlineRule for SubscriptScanner {
+    const MARKER: char = '~';
+
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let input = &state.src[state.pos..state.pos_max];
+        let rest = input.strip_prefix('~')?;
+
+        // `~~...~~` is strikethrough, not subscript
+        if rest.starts_with('~') {
+            return None;
+        }
+
+        let close = find_unescaped('~', rest)?;
+        let content = &rest[..close];
+        if content.is_empty() || content.chars().any(char::is_whitespace) {
+            return None;
+        }
+        // the closing `~` must not itself be the opening `~` of a `~~` run
+        if rest[close + 1..].starts_with('~') {
+            return None;
+        }
+
+        let mut node = Node::new(Subscript);
+        node.children.push(Node::new(
+            crate::mdparser::inline::builtin::skip_text::Text {
+                content: content.to_string(),
+            },
+        ));
+
+        Some((node, 1 + close + 1))
+    }
+}
@@ -0,0 +1,73 @@
+//! A [markdown_it] plugin for GFM-style superscript text: `^text^` renders
+//! as `<sup>text</sup>`.
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::extra::superscript::add(parser);
+//! let html = parser.parse("2^10^ is 1024").render();
+//! assert_eq!(html.trim(), "<p>2<sup>10</sup> is 1024</p>");
+//! ```
+use crate::mdparser::inline::{InlineRule, InlineState};
+use crate::{MarkdownIt, Node, NodeValue, Renderer};
+
+/// AST node for superscript text: `^text^`.
+#[derive(Debug)]
+pub struct Superscript;
+
+impl NodeValue for Superscript {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("sup", &node.attrs);
+        fmt.contents(&node.children);
+        fmt.close("sup");
+    }
+}
+
+/// Add the superscript extension to the parser.
+pub fn add(md: &mut MarkdownIt) {
+    md.inline.add_rule::<SuperscriptScanner>();
+}
+
+struct SuperscriptScanner;
+impl InlineRule for SuperscriptScanner {
+    const MARKER: char = '^';
+
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let input = &state.src[state.pos..state.pos_max];
+        let rest = input.strip_prefix('^')?;
+
+        // `^[...]` is an inline footnote, not superscript
+        if rest.starts_with('[') {
+            return None;
+        }
+
+        let close = find_unescaped('^', rest)?;
+        let content = &rest[..close];
+        if content.is_empty() || content.chars().any(char::is_whitespace) {
+            return None;
+        }
+
+        let mut node = Node::new(Superscript);
+        node.children.push(Node::new(
+            crate::mdparser::inline::builtin::skip_text::Text {
+                content: content.to_string(),
+            },
+        ));
+
+        Some((node, 1 + close + 1))
+    }
+}
+
+/// Find the offset of the next occurrence of `marker` in `s` that isn't
+/// escaped with a preceding backslash, or `None` if there isn't one.
+pub(super) fn find_unescaped(marker: char, s: &str) -> Option<usize> {
+    let mut iter = s.char_indices();
+    while let Some((i, c)) = iter.next() {
+        if c == '\\' {
+            iter.next();
+        } else if c == marker {
+            return Some(i);
+        }
+    }
+    None
+}
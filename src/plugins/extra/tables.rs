@@ -0,0 +1,245 @@
+//! GFM pipe tables.
+//!
+//! <https://github.github.com/gfm/#tables-extension->
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::extra::tables::add(parser);
+//! let html = parser.parse("| a | b |\n| --- | :-: |\n| 1 | 2 |\n").render();
+//! assert!(html.contains("<table>"));
+//! assert!(html.contains(r#"<th style="text-align:center">b</th>"#));
+//! ```
+use crate::mdparser::block::{BlockRule, BlockState};
+use crate::{MarkdownIt, Node, NodeValue, Renderer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableAlign {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+impl TableAlign {
+    fn css(self) -> Option<&'static str> {
+        match self {
+            TableAlign::None => None,
+            TableAlign::Left => Some("text-align:left"),
+            TableAlign::Center => Some("text-align:center"),
+            TableAlign::Right => Some("text-align:right"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Table;
+impl NodeValue for Table {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.cr();
+        fmt.open("table", &node.attrs);
+        fmt.cr();
+        fmt.contents(&node.children);
+        fmt.cr();
+        fmt.close("table");
+        fmt.cr();
+    }
+}
+
+#[derive(Debug)]
+pub struct TableHead;
+impl NodeValue for TableHead {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("thead", &node.attrs);
+        fmt.contents(&node.children);
+        fmt.close("thead");
+        fmt.cr();
+    }
+}
+
+#[derive(Debug)]
+pub struct TableBody;
+impl NodeValue for TableBody {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("tbody", &node.attrs);
+        fmt.contents(&node.children);
+        fmt.close("tbody");
+        fmt.cr();
+    }
+}
+
+#[derive(Debug)]
+pub struct TableRow;
+impl NodeValue for TableRow {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("tr", &node.attrs);
+        fmt.contents(&node.children);
+        fmt.close("tr");
+        fmt.cr();
+    }
+}
+
+#[derive(Debug)]
+pub struct TableCell {
+    pub header: bool,
+    pub align: TableAlign,
+}
+impl NodeValue for TableCell {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let tag = if self.header { "th" } else { "td" };
+        let mut attrs = node.attrs.clone();
+        if let Some(style) = self.align.css() {
+            attrs.push(("style", style.to_string()));
+        }
+        fmt.open(tag, &attrs);
+        fmt.contents(&node.children);
+        fmt.close(tag);
+    }
+}
+
+/// Add the tables plugin to the parser
+pub fn add(md: &mut MarkdownIt) {
+    md.block.add_rule::<TableScanner>();
+}
+
+#[doc(hidden)]
+pub struct TableScanner;
+
+/// Split a table row into cell strings on unescaped `|`, trimming
+/// surrounding whitespace and the leading/trailing pipe, and turning
+/// `\|` into a literal pipe that doesn't split the cell.
+fn split_cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.peek() == Some(&'|') {
+            current.push('|');
+            chars.next();
+        } else if c == '|' {
+            cells.push(current.trim().to_string());
+            current = String::new();
+        } else {
+            current.push(c);
+        }
+    }
+    cells.push(current.trim().to_string());
+    cells
+}
+
+/// Parse a delimiter row (e.g. `| :-- | :-: | --: |`) into one [TableAlign]
+/// per column, or `None` if the line isn't a valid delimiter row.
+fn parse_delimiter_row(line: &str) -> Option<Vec<TableAlign>> {
+    if !line.contains('-') {
+        return None;
+    }
+
+    let cells = split_cells(line);
+    if cells.is_empty() {
+        return None;
+    }
+
+    cells
+        .iter()
+        .map(|cell| {
+            let left = cell.starts_with(':');
+            let right = cell.ends_with(':');
+            let dashes = cell.trim_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (left, right) {
+                (true, true) => TableAlign::Center,
+                (true, false) => TableAlign::Left,
+                (false, true) => TableAlign::Right,
+                (false, false) => TableAlign::None,
+            })
+        })
+        .collect()
+}
+
+fn build_row(mut cells: Vec<String>, aligns: &[TableAlign], header: bool, md: &MarkdownIt) -> Node {
+    // ragged rows are padded with empty cells, or truncated, to match the header
+    cells.resize(aligns.len(), String::new());
+    cells.truncate(aligns.len());
+
+    let mut row = Node::new(TableRow);
+    for (cell, align) in cells.into_iter().zip(aligns) {
+        let mut cell_node = Node::new(TableCell {
+            header,
+            align: *align,
+        });
+        cell_node.children = crate::mdparser::inline::parse(&cell, md);
+        row.children.push(cell_node);
+    }
+    row
+}
+
+impl BlockRule for TableScanner {
+    fn check(state: &mut BlockState) -> Option<()> {
+        if state.line_indent(state.line) >= state.md.max_indent {
+            return None;
+        }
+        if !state.get_line(state.line).contains('|') {
+            return None;
+        }
+        if state.line + 1 >= state.line_max {
+            return None;
+        }
+        parse_delimiter_row(state.get_line(state.line + 1))?;
+        Some(())
+    }
+
+    fn run(state: &mut BlockState) -> Option<(Node, usize)> {
+        let header_line = state.get_line(state.line).to_owned();
+        let delim_line = state.get_line(state.line + 1).to_owned();
+        let aligns = parse_delimiter_row(&delim_line)?;
+
+        let header_cells = split_cells(&header_line);
+        if header_cells.len() != aligns.len() {
+            // the delimiter row defines the column count; a header that
+            // doesn't match it is not a table (mirrors GFM's reference impl)
+            return None;
+        }
+
+        let mut next_line = state.line + 2;
+        let mut body_rows = Vec::new();
+        while next_line < state.line_max {
+            let line = state.get_line(next_line);
+            if line.trim().is_empty() {
+                break;
+            }
+            if state.line_indent(next_line) >= state.md.max_indent {
+                break;
+            }
+            if !line.contains('|') && aligns.len() > 1 {
+                break;
+            }
+            body_rows.push(split_cells(line));
+            next_line += 1;
+        }
+
+        let mut table = Node::new(Table);
+
+        let mut thead = Node::new(TableHead);
+        thead
+            .children
+            .push(build_row(header_cells, &aligns, true, state.md));
+        table.children.push(thead);
+
+        if !body_rows.is_empty() {
+            let mut tbody = Node::new(TableBody);
+            for row in body_rows {
+                tbody.children.push(build_row(row, &aligns, false, state.md));
+            }
+            table.children.push(tbody);
+        }
+
+        Some((table, next_line - state.line))
+    }
+}
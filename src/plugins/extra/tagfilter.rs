@@ -0,0 +1,60 @@
+//! GFM's [disallowed raw HTML (tagfilter)](https://github.github.com/gfm/#disallowed-raw-html-extension-)
+//! extension: neutralizes a fixed blacklist of raw HTML tags by escaping
+//! their leading `<` to `&lt;`, without disabling raw HTML altogether.
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::html::add(parser);
+//! quickmark::plugins::extra::tagfilter::add(parser);
+//! let html = parser.parse("<script>alert(1)</script>").render();
+//! assert!(html.contains("&lt;script>"));
+//! assert!(html.contains("&lt;/script>"));
+//! ```
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::mdparser::core::CoreRule;
+use crate::plugins::html::html_block::HtmlBlock;
+use crate::plugins::html::html_inline::HtmlInline;
+use crate::{MarkdownIt, Node};
+
+/// Tags GFM considers unsafe to leave as raw HTML, matched case-insensitively.
+const FILTERED_TAGS: &str = "title|textarea|style|xmp|iframe|noembed|noframes|script|plaintext";
+
+/// Matches `<tag` / `</tag` (case-insensitively) where `tag` is one of
+/// [`FILTERED_TAGS`] and is immediately followed by whitespace, `/`, or
+/// `>`, so it doesn't also match e.g. `<scriptable>`. The following
+/// character is captured (group 3) and written back unchanged.
+static TAGFILTER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r#"(?i)<(/?)({FILTERED_TAGS})([\s/>])"#
+    ))
+    .unwrap()
+});
+
+/// Replace every filtered tag's leading `<` with `&lt;` in `content`.
+pub fn filter_tags(content: &str) -> String {
+    TAGFILTER_PATTERN.replace_all(content, "&lt;${1}${2}${3}").to_string()
+}
+
+/// Add the tagfilter extension to the parser.
+pub fn add(md: &mut MarkdownIt) {
+    md.add_rule::<TagFilterRule>();
+}
+
+/// A [CoreRule] run after inline parsing that neutralizes [`FILTERED_TAGS`]
+/// in raw-HTML inline and block nodes.
+pub struct TagFilterRule;
+impl CoreRule for TagFilterRule {
+    fn run(root: &mut Node, _md: &MarkdownIt) {
+        root.walk_mut(|node, _| {
+            if let Some(value) = node.cast_mut::<HtmlBlock>() {
+                value.content = filter_tags(&value.content);
+            }
+            if let Some(value) = node.cast_mut::<HtmlInline>() {
+                value.content = filter_tags(&value.content);
+            }
+        });
+    }
+}
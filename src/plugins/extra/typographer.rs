@@ -0,0 +1,92 @@
+//! Textual "typography" replacements: `(c)` -> `©`, `(r)` -> `®`, `(tm)` ->
+//! `™`, and `--`/`---` -> en/em dash.
+//!
+//! Uses [`Node::walk_mut_enter_exit`](crate::Node::walk_mut_enter_exit) to
+//! track a `link_level` accumulator that's incremented on entering a
+//! [`Link`]/[`Autolink`] subtree and decremented on exit, so replacements
+//! never apply to link text or URLs.
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::extra::typographer::add(parser);
+//! let html = parser.parse("(c) 2024 [(c) Acme](http://example.com)").render();
+//! assert!(html.contains("© 2024"));
+//! assert!(html.contains(">(c) Acme</a>"));
+//! ```
+use crate::mdparser::core::CoreRule;
+use crate::mdparser::inline::builtin::skip_text::Text;
+use crate::plugins::cmark::inline::autolink::Autolink;
+use crate::plugins::cmark::inline::link::Link;
+use crate::{MarkdownIt, Node};
+
+/// Apply the fixed set of textual typography replacements to `s`. `---` is
+/// replaced before `--` so an em dash isn't first matched as two en dashes.
+fn replace_typography(s: &str) -> String {
+    s.replace("(tm)", "\u{2122}")
+        .replace("(TM)", "\u{2122}")
+        .replace("(c)", "\u{00A9}")
+        .replace("(C)", "\u{00A9}")
+        .replace("(r)", "\u{00AE}")
+        .replace("(R)", "\u{00AE}")
+        .replace("---", "\u{2014}")
+        .replace("--", "\u{2013}")
+}
+
+/// Add the typographer extension to the parser.
+pub fn add(md: &mut MarkdownIt) {
+    md.add_rule::<TypographerRule>();
+}
+
+struct TypographerRule;
+impl CoreRule for TypographerRule {
+    fn run(root: &mut Node, _md: &MarkdownIt) {
+        let mut link_level: u32 = 0;
+        root.walk_mut_enter_exit(
+            &mut link_level,
+            |node, _depth, link_level| {
+                if node.is::<Link>() || node.is::<Autolink>() {
+                    *link_level += 1;
+                    return;
+                }
+                if *link_level > 0 {
+                    return;
+                }
+                if let Some(text) = node.cast_mut::<Text>() {
+                    text.content = replace_typography(&text.content);
+                }
+            },
+            |node, _depth, link_level| {
+                if node.is::<Link>() || node.is::<Autolink>() {
+                    *link_level -= 1;
+                }
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn skips_typography_replacements_inside_links() {
+        let parser = &mut crate::MarkdownIt::new();
+        crate::plugins::cmark::add(parser);
+        super::add(parser);
+        let html = parser
+            .parse("(c) 2024 [(c) Acme](http://example.com)")
+            .render();
+        assert!(html.contains("\u{00A9} 2024"));
+        assert!(html.contains(">(c) Acme</a>"));
+    }
+
+    #[test]
+    fn replaces_dashes_and_trademark() {
+        let parser = &mut crate::MarkdownIt::new();
+        crate::plugins::cmark::add(parser);
+        super::add(parser);
+        let html = parser.parse("em---dash, en--dash, Brand(tm)").render();
+        assert!(html.contains("em\u{2014}dash"));
+        assert!(html.contains("en\u{2013}dash"));
+        assert!(html.contains("Brand\u{2122}"));
+    }
+}
@@ -0,0 +1,94 @@
+//! Adds anchor(s) to each footnote definition, linking back to the site(s)
+//! that referenced it.
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::footnote::add(parser);
+//! let html = parser.parse("note[^a] and again[^a]\n\n[^a]: the footnote\n").render();
+//! assert!(html.contains(r#"<a href="#fnref1" class="footnote-backref">↩</a>"#));
+//! assert!(html.contains(r#"<a href="#fnref2" class="footnote-backref">↩︎2</a>"#));
+//! ```
+use crate::mdparser::core::{CoreRule, Root};
+use crate::plugins::footnote::{definitions::FootnoteDefinition, FootnoteMap};
+use crate::{MarkdownIt, Node, NodeValue, Renderer};
+
+/// Add the footnote back-reference plugin to the parser
+pub fn add(md: &mut MarkdownIt) {
+    md.add_rule::<FootnoteBackRefRule>();
+}
+
+/// A single `↩` anchor pointing back to the reference site that cited this
+/// definition. `index` is the 1-based position among all references to the
+/// same definition; only the 2nd and later anchors render a number.
+#[derive(Debug)]
+pub struct FootnoteBackref {
+    pub ref_id: usize,
+    pub index: usize,
+}
+
+impl NodeValue for FootnoteBackref {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let mut attrs = node.attrs.clone();
+        attrs.push(("href", format!("#fnref{}", self.ref_id)));
+        attrs.push(("class", "footnote-backref".into()));
+
+        fmt.open("a", &attrs);
+        if self.index <= 1 {
+            fmt.text_raw("↩");
+        } else {
+            fmt.text_raw(&format!("↩︎{}", self.index));
+        }
+        fmt.close("a");
+    }
+}
+
+// This is an extension for the parser's core rule chain.
+// It must run after `collect`, since it needs `FootnoteDefinition` nodes
+// already moved into their final container.
+struct FootnoteBackRefRule;
+
+impl CoreRule for FootnoteBackRefRule {
+    fn run(root: &mut Node, _: &MarkdownIt) {
+        let data = root.cast_mut::<Root>().unwrap();
+        let root_ext = std::mem::take(&mut data.ext);
+        let map = match root_ext.get::<FootnoteMap>() {
+            Some(map) => map,
+            None => {
+                let data = root.cast_mut::<Root>().unwrap();
+                data.ext = root_ext;
+                return;
+            }
+        };
+
+        root.walk_mut(|node, _| {
+            let Some(def_id) = node
+                .cast::<FootnoteDefinition>()
+                .and_then(|def| def.def_id)
+            else {
+                return;
+            };
+
+            let refs = map.referenced_by(def_id);
+            if refs.is_empty() {
+                return;
+            }
+
+            // anchors belong at the end of the last block in the definition,
+            // so they read naturally as the final words of the footnote
+            let Some(last_block) = node.children.last_mut() else {
+                return;
+            };
+
+            for (idx, ref_id) in refs.into_iter().enumerate() {
+                last_block.children.push(Node::new(FootnoteBackref {
+                    ref_id,
+                    index: idx + 1,
+                }));
+            }
+        });
+
+        let data = root.cast_mut::<Root>().unwrap();
+        data.ext = root_ext;
+    }
+}
@@ -30,8 +30,9 @@ use crate::{
     MarkdownIt, Node, NodeValue,
 };
 
+use crate::plugins::diagnostics::{Diagnostic, Diagnostics, DUPLICATE_FOOTNOTE_DEFINITION};
 use crate::plugins::footnote::{
-    definitions::FootnoteDefinition, 
+    definitions::FootnoteDefinition,
     FootnoteMap,
 };
 
@@ -79,14 +80,19 @@ impl CoreRule for FootnoteCollectRule {
         // TODO this seems very cumbersome
         // but it is also how the crate::InlineParserRule works
         let data = root.cast_mut::<Root>().unwrap();
-        let root_ext = std::mem::take(&mut data.ext);
+        let mut root_ext = std::mem::take(&mut data.ext);
         let map = match root_ext.get::<FootnoteMap>() {
             Some(map) => map,
-            None => return,
+            None => {
+                let data = root.cast_mut::<Root>().unwrap();
+                data.ext = root_ext;
+                return;
+            }
         };
 
         // walk through the AST and extract all footnote definitions
         let mut defs = vec![];
+        let mut duplicates = vec![];
         root.walk_mut(|node, _| {
             // TODO could use drain_filter if it becomes stable: https://github.com/rust-lang/rust/issues/43244
             // defs.extend(
@@ -107,7 +113,17 @@ impl CoreRule for FootnoteCollectRule {
                                         continue;
                                     }
                                 }
-                                None => continue,
+                                // `FootnoteMap::add_def` returned `None` for this
+                                // definition's label, i.e. a definition already
+                                // exists for it; drop this one but remember it so
+                                // linting callers can flag the duplicate
+                                None => {
+                                    duplicates.push((
+                                        def_node.label.clone(),
+                                        extracted.srcmap,
+                                    ));
+                                    continue;
+                                }
                             }
                             if def_node.inline {
                                 // for inline footnotes,
@@ -124,7 +140,24 @@ impl CoreRule for FootnoteCollectRule {
             }
             node.children.retain(|child| !child.is::<PlaceholderNode>());
         });
+
+        if !duplicates.is_empty() {
+            let diagnostics = root_ext.get_or_insert_default::<Diagnostics>();
+            for (label, srcmap) in duplicates {
+                let label = label.unwrap_or_default();
+                let span = srcmap.map(|(start, end)| start..end).unwrap_or(0..0);
+                let message = format!("footnote definition [^{label}] is already defined");
+                diagnostics.0.push(Diagnostic::new_internal(
+                    DUPLICATE_FOOTNOTE_DEFINITION,
+                    span,
+                    message,
+                ));
+            }
+        }
+
         if defs.is_empty() {
+            let data = root.cast_mut::<Root>().unwrap();
+            data.ext = root_ext;
             return;
         }
 
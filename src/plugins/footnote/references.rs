@@ -20,6 +20,7 @@
 use crate::mdparser::inline::{InlineRule, InlineState};
 use crate::{MarkdownIt, Node, NodeValue, Renderer};
 
+use crate::plugins::diagnostics::{Diagnostic, Diagnostics, DANGLING_FOOTNOTE_REFERENCE};
 use crate::plugins::footnote::FootnoteMap;
 
 /// Add the footnote reference parsing to the markdown parser
@@ -90,15 +91,29 @@ impl InlineRule for FootnoteReferenceScanner {
             return None;
         }
 
+        let length = label.len() + 3; // 3 for '[^' and ']'
+
         let definitions = state.root_ext.get_or_insert_default::<FootnoteMap>();
         let (def_id, ref_id) = match definitions.add_ref(&label) {
             Some(value) => value,
-            // no definition found so this is not a footnote reference
-            None => return None,
+            None => {
+                // no definition found so this is not a footnote reference;
+                // record it so linting callers can flag the dangling ref
+                let span = state.pos..(state.pos + length);
+                let message = format!("footnote reference [^{label}] has no matching definition");
+                state
+                    .root_ext
+                    .get_or_insert_default::<Diagnostics>()
+                    .0
+                    .push(Diagnostic::new_internal(
+                        DANGLING_FOOTNOTE_REFERENCE,
+                        span,
+                        message,
+                    ));
+                return None;
+            }
         };
 
-        let length = label.len() + 3; // 3 for '[^' and ']'
-
         // return new node and length of this structure
         Some((
             Node::new(FootnoteReference {
@@ -0,0 +1,135 @@
+//! Bare URL autolinking
+//!
+//! Detects unlinked `http://`/`https://` (and, optionally, `www.`) URLs
+//! sitting in plain text, e.g. `see https://example.com/ for details`, and
+//! turns them into real links. CommonMark itself only autolinks
+//! `<http://example.com>` (angle brackets); this is purely an authoring
+//! convenience on top, so it's toggleable via [AutolinkUrlExtensionPlugin]
+//! and never touches angle-bracket autolinks or explicit `[text](url)`
+//! links, since those are consumed by their own inline rules before a bare
+//! `h`/`w` would be reached here.
+use crate::mdparser::inline::{InlineRule, InlineState};
+use crate::plugin_config::AutolinkUrlExtensionPlugin;
+use crate::{MarkdownIt, Node, NodeValue, Renderer};
+
+/// Find the end (in bytes) of the bare URL starting at the beginning of
+/// `input`: consume up to the first whitespace/`<`/`>`, then trim trailing
+/// punctuation that's almost always sentence punctuation rather than part
+/// of the URL (e.g. the `.` ending `see https://example.com.`), balancing
+/// a trailing `)` against `(` that appears earlier in the match so URLs
+/// like `https://en.wikipedia.org/wiki/Rust_(disambiguation)` keep their
+/// closing parenthesis.
+fn scan_url_end(input: &str) -> usize {
+    let mut end = 0;
+    for c in input.chars() {
+        if c.is_whitespace() || c == '<' || c == '>' {
+            break;
+        }
+        end += c.len_utf8();
+    }
+
+    let mut url = &input[..end];
+    loop {
+        let Some(last) = url.chars().last() else {
+            break;
+        };
+        match last {
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '"' | '*' => {
+                url = &url[..url.len() - last.len_utf8()];
+            }
+            ')' if url.matches(')').count() > url.matches('(').count() => {
+                url = &url[..url.len() - 1];
+            }
+            _ => break,
+        }
+    }
+
+    url.len()
+}
+
+/// Bare URLs should only be recognized at a word boundary, so `xhttp://`
+/// or `cwww.example.com` embedded in a larger word are left alone.
+fn at_word_boundary(state: &InlineState) -> bool {
+    state
+        .src
+        .get(..state.pos)
+        .and_then(|s| s.chars().rev().next())
+        .map(|c| !c.is_alphanumeric())
+        .unwrap_or(true)
+}
+
+#[derive(Debug)]
+pub struct AutolinkUrl {
+    pub url: String,
+}
+
+impl NodeValue for AutolinkUrl {
+    fn render(&self, _: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("a", &[("href", self.url.clone())]);
+        fmt.text(&self.url);
+        fmt.close("a");
+    }
+}
+
+struct HttpAutolinkScanner;
+
+impl InlineRule for HttpAutolinkScanner {
+    const MARKER: char = 'h';
+
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let config = state.md.ext.get::<AutolinkUrlExtensionPlugin>().copied()?;
+        if !config.enabled || !at_word_boundary(state) {
+            return None;
+        }
+
+        let input = &state.src[state.pos..state.pos_max];
+        let scheme_len = if input.starts_with("https://") {
+            8
+        } else if input.starts_with("http://") {
+            7
+        } else {
+            return None;
+        };
+
+        let len = scan_url_end(input);
+        if len <= scheme_len {
+            return None;
+        }
+
+        Some((Node::new(AutolinkUrl { url: input[..len].to_string() }), len))
+    }
+}
+
+struct WwwAutolinkScanner;
+
+impl InlineRule for WwwAutolinkScanner {
+    const MARKER: char = 'w';
+
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let config = state.md.ext.get::<AutolinkUrlExtensionPlugin>().copied()?;
+        if !config.enabled || !config.match_www || !at_word_boundary(state) {
+            return None;
+        }
+
+        let input = &state.src[state.pos..state.pos_max];
+        if !input.starts_with("www.") {
+            return None;
+        }
+
+        let len = scan_url_end(input);
+        if len <= "www.".len() {
+            return None;
+        }
+
+        Some((
+            Node::new(AutolinkUrl { url: format!("https://{}", &input[..len]) }),
+            len,
+        ))
+    }
+}
+
+pub fn add(md: &mut MarkdownIt, config: AutolinkUrlExtensionPlugin) {
+    md.ext.insert(config);
+    md.inline.add_rule::<HttpAutolinkScanner>();
+    md.inline.add_rule::<WwwAutolinkScanner>();
+}
@@ -1,5 +1,6 @@
 use crate::mdparser::inline::{InlineRule, InlineState};
 use crate::plugin_config::CitationExtensionPlugin;
+use crate::plugins::diagnostics::{Diagnostic, Diagnostics, OUT_OF_RANGE_CITATION};
 use crate::{MarkdownIt, Node, NodeValue, Renderer};
 use pyo3::prelude::*;
 
@@ -92,8 +93,6 @@ impl InlineRule for CitationInlineScanner {
         if !input.starts_with(OPEN_CITATION) || !input.contains(CLOSE_CITATION) {
             return None;
         }
-        let config = state.md.ext.get::<CitationExtensionPlugin>().unwrap();
-
         let citation_match = input.split_inclusive(CLOSE_CITATION).next()?;
 
         let citation_index: usize = citation_match
@@ -102,15 +101,31 @@ impl InlineRule for CitationInlineScanner {
             .parse()
             .ok()?;
 
-        let citation = config
-            .citations
-            .get(citation_index)?    
-            .clone();
+        let config = state.md.ext.get::<CitationExtensionPlugin>().unwrap();
+        let open_link_in_new_tab = config.open_links_in_new_tab;
+        let total_citations = config.citations.len();
+        let citation = config.citations.get(citation_index).cloned();
+
+        let citation = match citation {
+            Some(citation) => citation,
+            None => {
+                let span = state.pos..(state.pos + citation_match.len());
+                let message = format!(
+                    "citation index {citation_index} is out of range ({total_citations} citation(s) configured)"
+                );
+                state
+                    .root_ext
+                    .get_or_insert_default::<Diagnostics>()
+                    .0
+                    .push(Diagnostic::new_internal(OUT_OF_RANGE_CITATION, span, message));
+                return None;
+            }
+        };
 
         Some((
             Node::new(CitationNode {
                 citation,
-                open_link_in_new_tab: config.open_links_in_new_tab,
+                open_link_in_new_tab,
             }),
             citation_match.len(),
         ))
@@ -0,0 +1,103 @@
+//! Structured, source-spanned diagnostics for kagi plugins, rendered with
+//! `ariadne` the way nml surfaces parser errors: a byte range into the
+//! original source plus a human-readable label, instead of a bare string.
+//!
+//! [`math`](crate::plugins::kagi_plugins::math) and
+//! [`inkjet`](crate::plugins::kagi_plugins::inkjet) attach a
+//! [`SourceDiagnostic`] to nodes that failed to render as intended (a LaTeX
+//! parse failure, an unterminated code fence) while still falling back to
+//! their existing silent behavior for ordinary rendering.
+//! [`DiagnosticsCollectorRule`] gathers every attached diagnostic into
+//! [`SourceDiagnostics`] on the document root, for a linting/validation mode
+//! that wants to surface them to document authors.
+use std::ops::Range;
+
+use ariadne::{Label, Report, ReportKind, Source};
+
+use crate::mdparser::core::{CoreRule, Root};
+use crate::mdparser::extset::RootExt;
+use crate::plugins::kagi_plugins::inkjet::InkjetCodeFence;
+use crate::plugins::kagi_plugins::math_display::DisplayMath;
+use crate::plugins::kagi_plugins::math_inline::InlineMath;
+use crate::{MarkdownIt, Node};
+
+/// A single positioned diagnostic: `message` describes the failure,
+/// `label` annotates `span` (a byte range into `source`), and `source_id`
+/// names the source for the report header (e.g. `"inline math"`).
+#[derive(Debug, Clone)]
+pub struct SourceDiagnostic {
+    pub source_id: String,
+    pub source: String,
+    pub span: Range<usize>,
+    pub message: String,
+    pub label: String,
+}
+
+impl SourceDiagnostic {
+    pub fn new(
+        source_id: impl Into<String>,
+        source: impl Into<String>,
+        span: Range<usize>,
+        message: impl Into<String>,
+        label: impl Into<String>,
+    ) -> Self {
+        Self {
+            source_id: source_id.into(),
+            source: source.into(),
+            span,
+            message: message.into(),
+            label: label.into(),
+        }
+    }
+
+    /// Render this diagnostic as an `ariadne::Report`, returning the
+    /// formatted report text.
+    pub fn render(&self) -> String {
+        let mut buf = Vec::new();
+        let report = Report::build(ReportKind::Error, &self.source_id, self.span.start)
+            .with_message(&self.message)
+            .with_label(
+                Label::new((self.source_id.clone(), self.span.clone())).with_message(&self.label),
+            )
+            .finish();
+        // `write` only fails on an I/O error, which an in-memory `Vec<u8>`
+        // sink never produces.
+        report
+            .write((self.source_id.clone(), Source::from(&self.source)), &mut buf)
+            .expect("writing a report to an in-memory buffer cannot fail");
+        String::from_utf8_lossy(&buf).into_owned()
+    }
+}
+
+/// Every [`SourceDiagnostic`] collected from the document by
+/// [`DiagnosticsCollectorRule`], for callers running in a
+/// linting/validation mode rather than relying on the silent fallbacks used
+/// during ordinary rendering.
+#[derive(Debug, Default)]
+pub struct SourceDiagnostics(pub Vec<SourceDiagnostic>);
+impl RootExt for SourceDiagnostics {}
+
+/// A [CoreRule] that walks the document for math and code-fence nodes
+/// carrying a diagnostic and collects them into [`SourceDiagnostics`] on
+/// the root.
+pub(crate) struct DiagnosticsCollectorRule;
+impl CoreRule for DiagnosticsCollectorRule {
+    fn run(root: &mut Node, _: &MarkdownIt) {
+        let mut diagnostics = Vec::new();
+
+        root.walk(|node, _| {
+            if let Some(math) = node.cast::<InlineMath>() {
+                diagnostics.extend(math.diagnostic.clone());
+            } else if let Some(math) = node.cast::<DisplayMath>() {
+                diagnostics.extend(math.diagnostic.clone());
+            } else if let Some(fence) = node.cast::<InkjetCodeFence>() {
+                diagnostics.extend(fence.diagnostic.clone());
+            }
+        });
+
+        if !diagnostics.is_empty() {
+            let data = root.cast_mut::<Root>().unwrap();
+            data.ext.insert(SourceDiagnostics(diagnostics));
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Diagram code fences (`dot`/`graphviz`, `mermaid`) rendered as embedded
+//! SVG or client-rendered containers, mirroring nml's dedicated graphviz
+//! element. See [`inkjet`](crate::plugins::kagi_plugins::inkjet), which
+//! dispatches here before falling back to ordinary syntax highlighting.
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use cached::proc_macro::cached;
+
+/// Which diagram backend a fence's language token selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramKind {
+    /// Rendered server-side to inline SVG via an external `dot`-compatible
+    /// layout command.
+    Graphviz,
+    /// Emitted as raw source inside a `<pre class="mermaid">` container for
+    /// a front-end script (e.g. mermaid.js) to render client-side.
+    Mermaid,
+}
+
+impl DiagramKind {
+    /// Resolve a fence's language token to a diagram backend, if `lang_name`
+    /// appears in the configured `renderers` set (case-insensitively).
+    pub fn from_lang(lang_name: &str, renderers: &[String]) -> Option<Self> {
+        if !renderers.iter().any(|r| r.eq_ignore_ascii_case(lang_name)) {
+            return None;
+        }
+        match lang_name.to_ascii_lowercase().as_str() {
+            "dot" | "graphviz" => Some(Self::Graphviz),
+            "mermaid" => Some(Self::Mermaid),
+            _ => None,
+        }
+    }
+}
+
+fn hash_str(s: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    s.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Invoke `binary_path` (a `dot`-compatible layout command) on `source` and
+/// return the resulting SVG, cached like [`math_render_cached`](crate::plugins::kagi_plugins::math::math_render_cached).
+/// Returns `None` when the binary is missing or exits with an error, so a
+/// missing tool never breaks the document -- callers should fall back to
+/// ordinary highlighting in that case.
+#[cached(
+    size = 128,
+    key = "(u64, String)",
+    convert = r#"{ (hash_str(&source), binary_path.clone()) }"#
+)]
+pub fn render_graphviz_svg_cached(source: String, binary_path: String) -> Option<String> {
+    render_graphviz_svg(&source, &binary_path)
+}
+
+fn render_graphviz_svg(source: &str, binary_path: &str) -> Option<String> {
+    let mut child = Command::new(binary_path)
+        .arg("-Tsvg")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    child.stdin.take()?.write_all(source.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Wrap `source` in a `<pre class="mermaid">` container carrying the raw
+/// diagram text, for a front-end script to render client-side.
+pub fn render_mermaid_container(source: &str) -> String {
+    format!(
+        "<pre class=\"mermaid\">{}</pre>",
+        v_htmlescape::escape(source)
+    )
+}
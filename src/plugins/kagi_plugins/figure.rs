@@ -0,0 +1,137 @@
+//! Named cross-references for [`Image`] figures, modeled on nml's
+//! media/reference subsystem: an image declared with a trailing
+//! `{#refname}` suffix (see [`image`](crate::plugins::kagi_plugins::image))
+//! is assigned a sequential figure number, and `§fig:refname` anywhere else
+//! in the document resolves to an auto-numbered link ("Figure 3").
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::kagi_plugins::add(parser);
+//! let html = parser
+//!     .parse("![a diagram](diagram.png){#diagram}\n\nSee §fig:diagram.\n")
+//!     .render();
+//! assert!(html.contains("<figure id=\"diagram\">"));
+//! assert!(html.contains("Figure 1: a diagram"));
+//! assert!(html.contains(r#"<a href="#diagram">Figure 1</a>"#));
+//! ```
+use std::collections::HashMap;
+
+use crate::mdparser::core::{CoreRule, Root};
+use crate::mdparser::extset::RootExt;
+use crate::mdparser::inline::{InlineRule, InlineState};
+use crate::plugins::kagi_plugins::image::Image;
+use crate::{MarkdownIt, Node, NodeValue, Renderer};
+
+/// Sequential figure numbers assigned to each named image, keyed by
+/// refname, plus any diagnostics raised while assigning them (duplicate
+/// refnames or references to a name that was never declared).
+#[derive(Debug, Default)]
+pub struct FigureRegistry {
+    pub numbers: HashMap<String, usize>,
+    pub diagnostics: Vec<String>,
+}
+impl RootExt for FigureRegistry {}
+
+/// A `§fig:name` reference to a named figure. Resolved to a figure number
+/// by [`FigureNumberingRule`]; stays `None` (and renders the raw marker)
+/// when the name was never declared by an [`Image`].
+#[derive(Debug)]
+pub struct FigureRef {
+    pub name: String,
+    pub number: Option<usize>,
+}
+
+impl NodeValue for FigureRef {
+    fn render(&self, _node: &Node, fmt: &mut dyn Renderer) {
+        let Some(number) = self.number else {
+            // unresolved reference: leave the raw marker visible rather
+            // than silently dropping it
+            fmt.text(&format!("§fig:{}", self.name));
+            return;
+        };
+
+        let attrs = [("href", format!("#{}", self.name))];
+        fmt.open("a", &attrs);
+        fmt.text(&format!("Figure {}", number));
+        fmt.close("a");
+    }
+}
+
+struct FigureRefScanner;
+impl InlineRule for FigureRefScanner {
+    const MARKER: char = '§';
+
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let input = &state.src[state.pos..state.pos_max];
+        let rest = input.strip_prefix("§fig:")?;
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return None;
+        }
+
+        Some((
+            Node::new(FigureRef {
+                name: rest[..end].to_string(),
+                number: None,
+            }),
+            "§fig:".len() + end,
+        ))
+    }
+}
+
+/// Add the figure cross-reference plugin. Must be added after
+/// [`image::add`](crate::plugins::kagi_plugins::image::add) so `Image`
+/// nodes already carry their `refname`.
+pub fn add(md: &mut MarkdownIt) {
+    md.inline.add_rule::<FigureRefScanner>();
+    md.add_rule::<FigureNumberingRule>();
+}
+
+/// A [CoreRule] that assigns sequential figure numbers to named images (in
+/// document order), flags duplicate refnames, and resolves every
+/// [`FigureRef`] against that numbering.
+struct FigureNumberingRule;
+impl CoreRule for FigureNumberingRule {
+    fn run(root: &mut Node, _: &MarkdownIt) {
+        let mut registry = FigureRegistry::default();
+
+        root.walk(|node, _| {
+            let Some(refname) = node.cast::<Image>().and_then(|img| img.refname.clone()) else {
+                return;
+            };
+            if registry.numbers.contains_key(&refname) {
+                registry
+                    .diagnostics
+                    .push(format!("duplicate figure refname {:?}", refname));
+                return;
+            }
+            let number = registry.numbers.len() + 1;
+            registry.numbers.insert(refname, number);
+        });
+
+        root.walk_mut(|node, _| {
+            if let Some(image) = node.cast_mut::<Image>() {
+                if let Some(refname) = &image.refname {
+                    image.number = registry.numbers.get(refname).copied();
+                }
+                return;
+            }
+
+            if let Some(figure_ref) = node.cast_mut::<FigureRef>() {
+                figure_ref.number = registry.numbers.get(&figure_ref.name).copied();
+                if figure_ref.number.is_none() {
+                    registry
+                        .diagnostics
+                        .push(format!("undefined figure reference {:?}", figure_ref.name));
+                }
+            }
+        });
+
+        if !registry.numbers.is_empty() || !registry.diagnostics.is_empty() {
+            let data = root.cast_mut::<Root>().unwrap();
+            data.ext.insert(registry);
+        }
+    }
+}
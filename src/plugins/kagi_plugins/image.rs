@@ -6,6 +6,7 @@
 use crate::mdparser::inline::{InlineRule, InlineState};
 use crate::plugin_config::ImageExtensionPlugin;
 use crate::plugins::kagi_plugins::link::LINK_MD_PATTERN;
+use crate::plugins::refs::validate_refname;
 use crate::{MarkdownIt, Node, NodeValue, Renderer};
 use html_escape::decode_html_entities;
 
@@ -14,6 +15,17 @@ pub struct Image {
     pub url: Option<String>,
     pub title: String,
     pub config: ImageExtensionPlugin,
+    /// Name registered for this image by a trailing `{#refname}` suffix, so
+    /// it can be linked to by [`crate::plugins::kagi_plugins::figure::FigureRef`]
+    /// (`§fig:refname`).
+    pub refname: Option<String>,
+    /// Caption text from the `{#refname "caption"}` form; defaults to
+    /// `title` when a `refname` is present but no caption text was given.
+    pub caption: Option<String>,
+    /// Sequential figure number assigned by
+    /// [`crate::plugins::kagi_plugins::figure::FigureNumberingRule`] once a
+    /// `refname` is present; `None` until that rule has run.
+    pub number: Option<usize>,
 }
 
 impl NodeValue for Image {
@@ -29,10 +41,48 @@ impl NodeValue for Image {
         attrs.push(("alt", self.title.clone()));
         attrs.push(("src", url.clone()));
 
+        let Some(refname) = &self.refname else {
+            fmt.self_close("img", &attrs);
+            return;
+        };
+
+        fmt.open("figure", &[("id", refname.clone())]);
         fmt.self_close("img", &attrs);
+
+        let caption = self.caption.as_deref().unwrap_or(&self.title);
+        if !caption.is_empty() {
+            fmt.open("figcaption", &[]);
+            if let Some(number) = self.number {
+                fmt.text(&format!("Figure {}: ", number));
+            }
+            fmt.text(caption);
+            fmt.close("figcaption");
+        }
+
+        fmt.close("figure");
     }
 }
 
+/// Parse a trailing `{#refname}` or `{#refname "caption"}` figure suffix
+/// immediately following an image, returning the validated refname, the
+/// optional caption, and the number of bytes consumed. Malformed names
+/// (rejected by [`validate_refname`]) are simply not recognized as a
+/// suffix, leaving the `{...}` text untouched in the output.
+fn parse_figure_suffix(rest: &str) -> Option<(String, Option<String>, usize)> {
+    let body = rest.strip_prefix("{#")?;
+    let end = body.find('}')?;
+    let inner = &body[..end];
+
+    let mut parts = inner.splitn(2, char::is_whitespace);
+    let name = validate_refname(parts.next().unwrap_or("")).ok()?;
+    let caption = parts
+        .next()
+        .map(|s| s.trim().trim_matches('"').to_string())
+        .filter(|s| !s.is_empty());
+
+    Some((name, caption, end + 3))
+}
+
 struct ImageScanner;
 
 impl InlineRule for ImageScanner {
@@ -52,15 +102,27 @@ impl InlineRule for ImageScanner {
                 .name("url")
                 .map(|m| decode_html_entities(m.as_str()).to_string());
 
+            // NOTE(Rehan): + 1 for exclamation mark
+            // trim end to not replace trailing newline
+            let mut consumed = 1 + complete_match.trim_end().len();
+            let (refname, caption) = match parse_figure_suffix(&input[consumed..]) {
+                Some((name, caption, suffix_len)) => {
+                    consumed += suffix_len;
+                    (Some(name), caption)
+                }
+                None => (None, None),
+            };
+
             Some((
                 Node::new(Image {
                     url,
                     title: link_text,
                     config: *config,
+                    refname,
+                    caption,
+                    number: None,
                 }),
-                // NOTE(Rehan): + 1 for exclamation mark
-                // trim end to not replace trailing newline
-                1 + complete_match.trim_end().len(),
+                consumed,
             ))
         } else {
             None
@@ -4,14 +4,59 @@ use inkjet::{
     constants::HIGHLIGHT_CLASS_NAMES, formatter::Formatter, tree_sitter_highlight::HighlightEvent,
     Highlighter, Language, Result,
 };
+use once_cell::sync::Lazy;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+use syntect::parsing::SyntaxSet;
 use v_htmlescape;
 
 use crate::mdparser::block::{BlockRule, BlockState};
 use crate::mdparser::constants::INKJET_TO_PYGMENTS_CLASS_MAP;
 use crate::plugin_config::InkjetPlugin;
+use crate::plugins::kagi_plugins::diagnostic::SourceDiagnostic;
+use crate::plugins::kagi_plugins::diagram::{
+    render_graphviz_svg_cached, render_mermaid_container, DiagramKind,
+};
 use crate::{MarkdownIt, Node, NodeValue, Renderer};
 use std::cell::RefCell;
 
+// bundled fallback so `theme: None` still produces self-contained, styled output
+const DEFAULT_SYNTECT_THEME: &str = "InspiredGitHub";
+
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
+/// Highlight `content` with syntect, resolving `lang_name` to a syntax
+/// definition (falling back to plain text) and `theme_name` to a bundled
+/// theme (falling back to [`DEFAULT_SYNTECT_THEME`]), emitting inline
+/// `style="..."` spans so the output needs no external stylesheet.
+fn highlight_with_syntect(content: &str, lang_name: &str, theme_name: Option<&str>) -> String {
+    let syntax = SYNTAX_SET
+        .find_syntax_by_token(lang_name)
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+    let theme = theme_name
+        .and_then(|name| THEME_SET.themes.get(name))
+        .or_else(|| THEME_SET.themes.get(DEFAULT_SYNTECT_THEME))
+        .expect("bundled default syntect theme must be present");
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut html = String::new();
+    for line in content.lines() {
+        let Ok(regions) = highlighter.highlight_line(line, &SYNTAX_SET) else {
+            html.push_str(&v_htmlescape::escape(line).to_string());
+            html.push('\n');
+            continue;
+        };
+        html.push_str(
+            &styled_line_to_highlighted_html(&regions[..], IncludeBackground::No)
+                .unwrap_or_default(),
+        );
+        html.push('\n');
+    }
+    html
+}
+
 pub const CODE_HIGHLIGHT_SUFFIX: &str = "</code></pre></div>";
 // NOTE(Rehan): if we want to reuse the highlighter, it needs to be mutable
 // `thread_local!` runs once per thread. `RefCell` moves compile time borrow to runtime.
@@ -22,6 +67,27 @@ thread_local! {
 }
 pub struct PygmentsCompatibleFormatter {
     pub pygments_classes: bool,
+    /// When set, emit `style="color:#...;font-weight:bold"` attributes
+    /// resolved from this theme instead of a class name, so the output is
+    /// self-contained and needs no stylesheet (see [`InkjetTheme`]).
+    pub style_theme: Option<&'static InkjetTheme>,
+    // tree-sitter highlight events are emitted over the whole fence content
+    // in one pass, so a `<span ...>` can straddle a line boundary; track
+    // the currently-open opening tags so `Source` events can close and
+    // reopen them around embedded `\n`s, keeping each rendered line
+    // self-contained (wrapping a line in `<span class="hll">` later
+    // requires that it not carry an unbalanced tag into the next line)
+    open_spans: RefCell<Vec<String>>,
+}
+
+impl PygmentsCompatibleFormatter {
+    pub fn new(pygments_classes: bool, style_theme: Option<&'static InkjetTheme>) -> Self {
+        Self {
+            pygments_classes,
+            style_theme,
+            open_spans: RefCell::new(Vec::new()),
+        }
+    }
 }
 
 // NOTE(Rehan): based implementation here on default html formatter: https://docs.rs/crate/inkjet/latest/source/src/formatter/html.rs
@@ -36,20 +102,43 @@ impl Formatter for PygmentsCompatibleFormatter {
                 let span = source
                     .get(start..end)
                     .expect("Source bounds should be in bounds!");
-                write!(writer, "{}", v_htmlescape::escape(span))?;
+
+                let mut lines = span.split('\n');
+                if let Some(first) = lines.next() {
+                    write!(writer, "{}", v_htmlescape::escape(first))?;
+                }
+                for line in lines {
+                    for _ in self.open_spans.borrow().iter() {
+                        writer.write_str("</span>")?;
+                    }
+                    writer.write_str("\n")?;
+                    for tag in self.open_spans.borrow().iter() {
+                        writer.write_str(tag)?;
+                    }
+                    write!(writer, "{}", v_htmlescape::escape(line))?;
+                }
             }
             HighlightEvent::HighlightStart(idx) => {
                 let inkjet_class = HIGHLIGHT_CLASS_NAMES[idx.0];
-                let output_class: &&str = if self.pygments_classes {
-                    INKJET_TO_PYGMENTS_CLASS_MAP
-                        .get(inkjet_class)
-                        .unwrap_or(&inkjet_class)
-                } else {
-                    &inkjet_class
+                let tag = match self.style_theme.and_then(|theme| theme.resolve(inkjet_class)) {
+                    Some(style) => format!("<span style=\"{}\">", style.to_css()),
+                    None => {
+                        let output_class: &'static str = if self.pygments_classes {
+                            INKJET_TO_PYGMENTS_CLASS_MAP
+                                .get(inkjet_class)
+                                .copied()
+                                .unwrap_or(inkjet_class)
+                        } else {
+                            inkjet_class
+                        };
+                        format!("<span class=\"{}\">", output_class)
+                    }
                 };
-                write!(writer, "<span class=\"{}\">", output_class)?;
+                writer.write_str(&tag)?;
+                self.open_spans.borrow_mut().push(tag);
             }
             HighlightEvent::HighlightEnd => {
+                self.open_spans.borrow_mut().pop();
                 writer.write_str("</span>")?;
             }
         }
@@ -57,6 +146,206 @@ impl Formatter for PygmentsCompatibleFormatter {
     }
 }
 
+/// An inline style for one highlight scope: a CSS color plus bold/italic
+/// flags, as used by theme-driven inline-style highlighting.
+#[derive(Debug, Clone, Copy)]
+pub struct TokenStyle {
+    pub color: &'static str,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl TokenStyle {
+    const fn new(color: &'static str) -> Self {
+        Self {
+            color,
+            bold: false,
+            italic: false,
+        }
+    }
+
+    const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    const fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    fn to_css(self) -> String {
+        let mut css = format!("color:{}", self.color);
+        if self.bold {
+            css.push_str(";font-weight:bold");
+        }
+        if self.italic {
+            css.push_str(";font-style:italic");
+        }
+        css
+    }
+}
+
+/// A named color theme mapping dotted tree-sitter highlight scopes (as
+/// found in [`HIGHLIGHT_CLASS_NAMES`]) to a [`TokenStyle`]. A scope not
+/// found verbatim falls back to its nearest parent scope, e.g.
+/// `function.builtin` inherits `function`'s style when the theme has no
+/// entry of its own for `function.builtin`.
+#[derive(Debug)]
+pub struct InkjetTheme {
+    pub name: &'static str,
+    styles: &'static [(&'static str, TokenStyle)],
+}
+
+impl InkjetTheme {
+    fn resolve(&self, scope: &str) -> Option<TokenStyle> {
+        let mut candidate = scope;
+        loop {
+            if let Some((_, style)) = self.styles.iter().find(|(name, _)| *name == candidate) {
+                return Some(*style);
+            }
+            candidate = candidate.rsplit_once('.').map(|(parent, _)| parent)?;
+        }
+    }
+}
+
+static GITHUB_LIGHT_THEME: InkjetTheme = InkjetTheme {
+    name: "github-light",
+    styles: &[
+        ("keyword", TokenStyle::new("#d73a49").bold()),
+        ("function", TokenStyle::new("#6f42c1")),
+        ("string", TokenStyle::new("#032f62")),
+        ("comment", TokenStyle::new("#6a737d").italic()),
+        ("number", TokenStyle::new("#005cc5")),
+        ("constant", TokenStyle::new("#005cc5")),
+        ("type", TokenStyle::new("#22863a")),
+        ("variable", TokenStyle::new("#24292e")),
+        ("operator", TokenStyle::new("#d73a49")),
+        ("punctuation", TokenStyle::new("#24292e")),
+    ],
+};
+
+static GITHUB_DARK_THEME: InkjetTheme = InkjetTheme {
+    name: "github-dark",
+    styles: &[
+        ("keyword", TokenStyle::new("#ff7b72").bold()),
+        ("function", TokenStyle::new("#d2a8ff")),
+        ("string", TokenStyle::new("#a5d6ff")),
+        ("comment", TokenStyle::new("#8b949e").italic()),
+        ("number", TokenStyle::new("#79c0ff")),
+        ("constant", TokenStyle::new("#79c0ff")),
+        ("type", TokenStyle::new("#7ee787")),
+        ("variable", TokenStyle::new("#c9d1d9")),
+        ("operator", TokenStyle::new("#ff7b72")),
+        ("punctuation", TokenStyle::new("#c9d1d9")),
+    ],
+};
+
+/// Resolve a built-in inline-style theme by name, for use with
+/// [`InkjetPlugin::style_theme`](crate::plugin_config::InkjetPlugin).
+pub fn find_style_theme(name: &str) -> Option<&'static InkjetTheme> {
+    match name {
+        "github-light" => Some(&GITHUB_LIGHT_THEME),
+        "github-dark" => Some(&GITHUB_DARK_THEME),
+        _ => None,
+    }
+}
+
+/// Pygments/SuperFences-style options parsed from a fence's info string,
+/// e.g. ` linenums="3" hl_lines="2 4-6"`.
+#[derive(Debug, Default, Clone)]
+struct FenceOptions {
+    /// 1-based starting number for the `linenos` gutter; `None` means no
+    /// gutter is rendered.
+    linenums_start: Option<usize>,
+    /// 1-based line numbers to wrap in `<span class="hll">`.
+    hl_lines: Vec<usize>,
+}
+
+fn parse_fence_options(info: &str) -> FenceOptions {
+    let mut options = FenceOptions::default();
+    let mut rest = info;
+
+    while let Some(key_start) = rest.find(|c: char| !c.is_whitespace()) {
+        rest = &rest[key_start..];
+        let Some(eq) = rest.find('=') else {
+            break;
+        };
+        let key = rest[..eq].trim();
+        let Some(after_quote) = rest[eq + 1..].strip_prefix('"') else {
+            break;
+        };
+        let Some(value_end) = after_quote.find('"') else {
+            break;
+        };
+        let value = &after_quote[..value_end];
+
+        match key {
+            "linenums" => options.linenums_start = value.parse().ok(),
+            "hl_lines" => options.hl_lines = parse_line_ranges(value),
+            _ => {}
+        }
+
+        rest = &after_quote[value_end + 1..];
+    }
+
+    options
+}
+
+/// Parse a whitespace-separated list of line numbers and `start-end`
+/// ranges, e.g. `"2 4-6"` -> `[2, 4, 5, 6]`.
+fn parse_line_ranges(value: &str) -> Vec<usize> {
+    let mut lines = Vec::new();
+    for token in value.split_whitespace() {
+        if let Some((start, end)) = token.split_once('-') {
+            if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+                lines.extend(start..=end);
+            }
+        } else if let Ok(n) = token.parse::<usize>() {
+            lines.push(n);
+        }
+    }
+    lines
+}
+
+/// Wrap each 1-based line in `hl_lines` in `<span class="hll">...</span>`.
+/// Requires each line of `html` to already carry balanced tags (true for
+/// syntect's per-line output, and for [`PygmentsCompatibleFormatter`] once
+/// it closes/reopens spans at line boundaries).
+fn wrap_highlighted_lines(html: &str, hl_lines: &[usize]) -> String {
+    if hl_lines.is_empty() {
+        return html.to_string();
+    }
+
+    let hl_lines: std::collections::HashSet<usize> = hl_lines.iter().copied().collect();
+    let trailing_newline = html.ends_with('\n');
+    let mut out = html
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            if hl_lines.contains(&(idx + 1)) {
+                format!("<span class=\"hll\">{}</span>", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if trailing_newline {
+        out.push('\n');
+    }
+    out
+}
+
+/// Render the Pygments "linenos" gutter: sequential line numbers starting
+/// at `start`, one per line.
+fn render_linenos_gutter(start: usize, line_count: usize) -> String {
+    (start..start + line_count)
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[derive(Debug)]
 pub struct InkjetCodeFence {
     pub info: String,
@@ -64,41 +353,96 @@ pub struct InkjetCodeFence {
     pub marker_len: usize,
     pub content: String,
     pub use_pygments: bool,
+    pub theme: Option<String>,
+    /// Resolved inline-style theme for [`PygmentsCompatibleFormatter`]
+    /// (only meaningful when `use_pygments` is true); `None` keeps the
+    /// existing class-based output.
+    pub style_theme: Option<&'static InkjetTheme>,
+    /// Diagram backend selected by the fence's language token, if any; see
+    /// [`diagram`](crate::plugins::kagi_plugins::diagram). `None` renders as
+    /// ordinary highlighted code.
+    pub diagram: Option<DiagramKind>,
+    /// Binary used to render `DiagramKind::Graphviz` fences; only
+    /// meaningful when `diagram` is `Some(DiagramKind::Graphviz)`.
+    pub graphviz_binary_path: String,
+    /// Set when this fence was never closed with a matching marker line,
+    /// for a linting/validation mode (see
+    /// [`diagnostic`](crate::plugins::kagi_plugins::diagnostic)); the
+    /// document is still auto-closed and rendered as today regardless.
+    pub diagnostic: Option<SourceDiagnostic>,
 }
 
 impl NodeValue for InkjetCodeFence {
     fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        if self.diagram == Some(DiagramKind::Mermaid) {
+            fmt.cr();
+            fmt.text_raw(&render_mermaid_container(&self.content));
+            fmt.cr();
+            return;
+        }
+
+        if self.diagram == Some(DiagramKind::Graphviz) {
+            if let Some(svg) =
+                render_graphviz_svg_cached(self.content.clone(), self.graphviz_binary_path.clone())
+            {
+                fmt.cr();
+                fmt.text_raw(&svg);
+                fmt.cr();
+                return;
+            }
+            // binary missing or errored: fall through to ordinary highlighting
+        }
+
         let info = unescape_all(&self.info);
         let mut split = info.split_whitespace();
         let lang_name = split.next().unwrap_or("");
+        let options = parse_fence_options(info.splitn(2, char::is_whitespace).nth(1).unwrap_or(""));
+        let lang_enum = Language::from_token(lang_name).unwrap_or(Language::Plaintext);
         let mut attrs = node.attrs.clone();
 
         if !lang_name.is_empty() {
             attrs.push(("class", lang_name.to_string()));
         }
 
-        let formatter = PygmentsCompatibleFormatter {
-            pygments_classes: self.use_pygments,
-        };
+        let html = if self.use_pygments {
+            let formatter = PygmentsCompatibleFormatter::new(self.use_pygments, self.style_theme);
 
-        let lang_enum = Language::from_token(lang_name).unwrap_or(Language::Plaintext);
+            HIGHLIGHTER.with_borrow_mut(|h| {
+                h.highlight_to_string(lang_enum, &formatter, self.content.clone())
+                    .unwrap()
+            })
+        } else {
+            // self-contained, themeable output: no external stylesheet needed
+            highlight_with_syntect(&self.content, lang_name, self.theme.as_deref())
+        };
 
-        let html = HIGHLIGHTER.with_borrow_mut(|h| {
-            h.highlight_to_string(lang_enum, &formatter, self.content.clone())
-                .unwrap()
-        });
+        let html = wrap_highlighted_lines(&html, &options.hl_lines);
 
         // NOTE(Rehan): this is what our python code highlighting extension has wrapped around the actual highlighted code
         // so we'll wrap here as well for compatibility
         // `class="codehilite"` from SuperFences extension, `class="filename"` from Highlight extension
 
-        let html = format!(
+        let code_block = format!(
             "<div class=\"codehilite\">\
                     <span class=\"filename\">{:?}</span>\
                     <pre><span></span><code>{html}{CODE_HIGHLIGHT_SUFFIX}",
             lang_enum,
         );
 
+        let html = match options.linenums_start {
+            Some(start) => {
+                let line_count = self.content.lines().count().max(1);
+                let gutter = render_linenos_gutter(start, line_count);
+                format!(
+                    "<table class=\"highlighttable\"><tr>\
+                        <td class=\"linenos\"><div class=\"linenodiv\"><pre>{gutter}</pre></div></td>\
+                        <td class=\"code\">{code_block}</td>\
+                    </tr></table>"
+                )
+            }
+            None => code_block,
+        };
+
         fmt.cr();
         fmt.text_raw(&html);
         fmt.cr();
@@ -222,7 +566,39 @@ impl BlockRule for InkjetFenceScanner {
         let indent = state.line_offsets[state.line].indent_nonspace;
         let (content, _) = state.get_lines(state.line + 1, next_line, indent as usize, true);
 
-        let use_pygments = state.md.ext.get::<InkjetPlugin>().unwrap().pygments_classes;
+        let config = state.md.ext.get::<InkjetPlugin>().unwrap();
+        let use_pygments = config.pygments_classes;
+        let theme = config.theme.clone();
+        let style_theme = config
+            .style_theme
+            .as_deref()
+            .and_then(find_style_theme);
+        let lang_name = unescape_all(&params)
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        let diagram = DiagramKind::from_lang(&lang_name, &config.diagram_renderers);
+        let graphviz_binary_path = config
+            .graphviz_binary_path
+            .clone()
+            .unwrap_or_else(|| "dot".to_string());
+        let diagnostic = if have_end_marker {
+            None
+        } else {
+            let source_id = if lang_name.is_empty() {
+                "code fence".to_string()
+            } else {
+                format!("{} code fence", lang_name)
+            };
+            Some(SourceDiagnostic::new(
+                source_id,
+                content.clone(),
+                0..content.len(),
+                "unterminated code fence",
+                "this fence was never closed with a matching marker line",
+            ))
+        };
 
         let node = Node::new(InkjetCodeFence {
             info: params,
@@ -230,6 +606,11 @@ impl BlockRule for InkjetFenceScanner {
             marker_len: len,
             content,
             use_pygments,
+            theme,
+            style_theme,
+            diagram,
+            graphviz_binary_path,
+            diagnostic,
         });
 
         Some((
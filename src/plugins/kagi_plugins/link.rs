@@ -13,17 +13,133 @@ use url::Url;
 
 use super::is_url_to_be_proxied;
 
-/// Parse Youtube ID from url
+/// A resolved YouTube embed: the 11-character video id, plus an optional
+/// start offset (in seconds) parsed from a `t`/`start` query parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YoutubeEmbed {
+    pub id: String,
+    pub start: Option<u32>,
+}
+
+// path segments that name a YouTube feature/listing rather than a video,
+// so they must never be mistaken for a video id
+const YOUTUBE_RESERVED_SEGMENTS: &[&str] = &[
+    "channel", "c", "user", "browse", "playlist", "watch", "w", "embed", "e", "results",
+    "shared", "hashtag", "shorts", "movies", "feed",
+];
+
+// path segments after which the *next* segment is the video id
+const YOUTUBE_ID_MARKERS: &[&str] = &["embed", "shorts", "live", "v", "e"];
+
+fn is_valid_youtube_id(id: &str) -> bool {
+    id.len() == 11 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Parse a `1h2m3s`-style (or bare-seconds) time offset, as accepted by
+/// YouTube's own `t`/`start` query parameters.
+fn parse_time_offset(raw: &str) -> Option<u32> {
+    if let Ok(seconds) = raw.parse::<u32>() {
+        return Some(seconds);
+    }
+
+    let mut remaining = raw;
+    let mut total = 0u32;
+    let mut matched_any = false;
+    for (unit, multiplier) in [('h', 3600), ('m', 60), ('s', 1)] {
+        if let Some(idx) = remaining.find(unit) {
+            let (value, rest) = remaining.split_at(idx);
+            if value.is_empty() {
+                return None;
+            }
+            total += value.parse::<u32>().ok()? * multiplier;
+            matched_any = true;
+            remaining = &rest[1..];
+        }
+    }
+
+    matched_any.then_some(total)
+}
+
+/// Parse a YouTube/`youtu.be` URL into a resolved video id and optional
+/// start offset, distinguishing real video URLs (embeds, shorts, live,
+/// playlists-with-a-video, `watch?v=`) from listing/feature pages like
+/// `/playlist` or `/results` that are not videos at all.
 ///
 /// # Examples
 ///
 /// ```
-/// use quickmark::plugins::kagi_plugins::link::parse_youtube_id;
-/// assert_eq!(parse_youtube_id("https://www.youtube.com/shorts/test_id"), Some("test_id".to_string()));
-/// assert_eq!(parse_youtube_id("https://www.youtube.com/watch?v=test_id"), Some("test_id".to_string()));
-/// assert_eq!(parse_youtube_id("https://www.youtu.be/test_id"), Some("test_id".to_string()));
+/// use quickmark::plugins::kagi_plugins::link::{parse_youtube_id, YoutubeEmbed};
+/// assert_eq!(
+///     parse_youtube_id("https://www.youtube.com/shorts/dQw4w9WgXcQ"),
+///     Some(YoutubeEmbed { id: "dQw4w9WgXcQ".to_string(), start: None })
+/// );
+/// assert_eq!(
+///     parse_youtube_id("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=1m30s"),
+///     Some(YoutubeEmbed { id: "dQw4w9WgXcQ".to_string(), start: Some(90) })
+/// );
+/// assert_eq!(
+///     parse_youtube_id("https://www.youtu.be/dQw4w9WgXcQ"),
+///     Some(YoutubeEmbed { id: "dQw4w9WgXcQ".to_string(), start: None })
+/// );
+/// assert_eq!(parse_youtube_id("https://www.youtube.com/playlist?list=abc"), None);
 /// assert_eq!(parse_youtube_id("https://www.invalid.com"), None);
 /// ```
+pub fn parse_youtube_id(url: &str) -> Option<YoutubeEmbed> {
+    let parsed_url = Url::parse(url).ok()?;
+    let host = parsed_url.host_str()?;
+    if !(host.contains("youtube.") || host.contains("youtu.be")) {
+        return None;
+    }
+
+    let segments: Vec<&str> = parsed_url
+        .path_segments()
+        .map(|s| s.filter(|seg| !seg.is_empty()).collect())
+        .unwrap_or_default();
+
+    let query_id = if host.contains("youtu.be") {
+        None
+    } else {
+        parsed_url
+            .query_pairs()
+            .find(|(key, _)| key == "v")
+            .map(|(_, value)| value.into_owned())
+    };
+
+    let id = if host.contains("youtu.be") {
+        segments.first().map(|s| s.to_string())
+    } else {
+        query_id.clone().or_else(|| {
+            segments
+                .iter()
+                .position(|seg| YOUTUBE_ID_MARKERS.contains(seg))
+                .and_then(|idx| segments.get(idx + 1))
+                .filter(|seg| !YOUTUBE_RESERVED_SEGMENTS.contains(seg))
+                .map(|seg| seg.to_string())
+        })
+    };
+
+    let id = id.filter(|id| is_valid_youtube_id(id))?;
+
+    // a reserved first segment (e.g. `/playlist`, `/results`) means the URL
+    // names a listing/feature page rather than a video, UNLESS the id was
+    // already resolved from the `v=` query param (as with `/watch?v=...`)
+    // or the segment is itself an id marker (`/embed/...`, `/shorts/...`)
+    if query_id.is_none() {
+        if let Some(first) = segments.first() {
+            if YOUTUBE_RESERVED_SEGMENTS.contains(first) && !YOUTUBE_ID_MARKERS.contains(first) {
+                return None;
+            }
+        }
+    }
+
+    let start = parsed_url
+        .query_pairs()
+        .find(|(key, _)| key == "t" || key == "start")
+        .and_then(|(_, value)| parse_time_offset(&value));
+
+    Some(YoutubeEmbed { id, start })
+}
+
 pub static LINK_MD_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
         r"(?x)
@@ -39,23 +155,6 @@ pub static LINK_MD_PATTERN: Lazy<Regex> = Lazy::new(|| {
     .unwrap()
 });
 
-pub fn parse_youtube_id(url: &str) -> Option<String> {
-    let parsed_url = Url::parse(url).ok()?;
-
-    // Try to get video ID from query parameter 'v'
-    if let Some(query_pairs) = parsed_url.query_pairs().find(|(key, _)| key == "v") {
-        return Some(query_pairs.1.to_string());
-    }
-
-    // If no 'v' parameter found, get the last segment of the path
-    let path_final_segment = parsed_url
-        .path_segments()
-        .and_then(|segments| segments.last())?
-        .to_string();
-
-    (!path_final_segment.is_empty()).then_some(path_final_segment)
-}
-
 fn parse_url_options(url: &str) -> Option<(bool, bool)> {
     let parse_result = Url::parse(&url).ok()?;
     let netloc = parse_result.host_str()?;
@@ -98,12 +197,16 @@ impl NodeValue for Link {
         let (audio, is_youtube) = parse_url_options(proper_url).unwrap_or((false, false));
 
         if is_youtube && config.embed_third_party_content {
-            if let Some(video_id) = parse_youtube_id(url) {
+            if let Some(embed) = parse_youtube_id(url) {
+                let mut src = format!("https://www.youtube.com/embed/{}", embed.id);
+                if let Some(start) = embed.start {
+                    src.push_str(&format!("?start={}", start));
+                }
                 let iframe_attrs = vec![
                     // NOTE(Rehan): taken from share menu in youtube, might want to adjust height and width values in future.
                     ("width", "560".to_string()),
                     ("height", "315".to_string()),
-                    ("src", format!("https://www.youtube.com/embed/{}", video_id)),
+                    ("src", src),
                     ("frameborder", "0".to_string()),
                     ("allowfullscreen", "true".to_string()),
                 ];
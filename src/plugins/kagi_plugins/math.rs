@@ -1,19 +1,200 @@
 //! this file just holds the function to convert from mathml to html
 //! keep here for reuse between inline math and siplay math modules, as well as applying caching
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
 use cached::proc_macro::cached;
 use html_escape::encode_text;
 use pulldown_latex::config::DisplayMode;
 use pulldown_latex::RenderConfig;
 use pulldown_latex::{mathml::push_mathml, Parser, Storage};
+use pyo3::prelude::*;
+
+use crate::plugins::kagi_plugins::diagnostic::SourceDiagnostic;
+
+/// A user-defined LaTeX macro: `name` expands to `replacement`, with `#1`,
+/// `#2`, ... in `replacement` substituted by the corresponding argument,
+/// the way TeX's `\newcommand` works. Configured on
+/// [`InlineMathExtensionPlugin`](crate::plugin_config::InlineMathExtensionPlugin)/
+/// [`DisplayMathExtensionPlugin`](crate::plugin_config::DisplayMathExtensionPlugin)
+/// so documents can use shorthand like `\RR` or `\abs{x}` instead of
+/// repeating the full expansion everywhere.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct MathMacro {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub arg_count: usize,
+    #[pyo3(get)]
+    pub replacement: String,
+}
+
+#[pymethods]
+impl MathMacro {
+    #[new]
+    fn new(name: String, arg_count: usize, replacement: String) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(MathMacro {
+            name,
+            arg_count,
+            replacement,
+        })
+    }
+}
 
-#[cached(size = 128)]
-pub fn math_render_cached(math: String, block_display_mode: bool) -> String {
-    math_render(math, block_display_mode)
+/// Build a lookup table from a macro list, keyed by name, for
+/// [`expand_macros`].
+pub fn build_macro_table(macros: &[MathMacro]) -> HashMap<String, MathMacro> {
+    macros
+        .iter()
+        .map(|m| (m.name.clone(), m.clone()))
+        .collect()
 }
 
-pub fn math_render(math: String, block_display_mode: bool) -> String {
+/// A stable hash of the active macro set (order-independent, since
+/// `macros` is keyed by name), used to key [`math_render_cached`] so
+/// cached results don't leak across differing macro configurations.
+fn macro_table_hash(macros: &HashMap<String, MathMacro>) -> u64 {
+    let mut names: Vec<&String> = macros.keys().collect();
+    names.sort();
+
+    let mut hasher = DefaultHasher::new();
+    for name in names {
+        let m = &macros[name];
+        m.name.hash(&mut hasher);
+        m.arg_count.hash(&mut hasher);
+        m.replacement.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Expand every `\name` (and `\name{arg1}...{argN}`) occurrence of a
+/// registered macro in `math`, substituting `#1..#N` in its replacement
+/// text with the supplied argument bodies. Unregistered commands are left
+/// untouched. Expansion is a single pass (macros may not reference other
+/// macros) to keep behavior predictable and termination obvious.
+fn expand_macros(math: &str, macros: &HashMap<String, MathMacro>) -> String {
+    if macros.is_empty() {
+        return math.to_string();
+    }
+
+    let mut out = String::with_capacity(math.len());
+    let bytes = math.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            // copy the whole non-backslash run as one `str` slice (instead
+            // of casting individual bytes to `char`) so multi-byte UTF-8
+            // content survives untouched
+            let next_backslash = bytes[i..]
+                .iter()
+                .position(|&b| b == b'\\')
+                .map(|offset| i + offset)
+                .unwrap_or(bytes.len());
+            out.push_str(&math[i..next_backslash]);
+            i = next_backslash;
+            continue;
+        }
+
+        let name_start = i + 1;
+        let name_end = bytes[name_start..]
+            .iter()
+            .position(|b| !b.is_ascii_alphabetic())
+            .map(|offset| name_start + offset)
+            .unwrap_or(bytes.len());
+
+        let Some(name) = math.get(name_start..name_end).filter(|s| !s.is_empty()) else {
+            out.push('\\');
+            i += 1;
+            continue;
+        };
+
+        let Some(macro_def) = macros.get(name) else {
+            out.push_str(&math[i..name_end]);
+            i = name_end;
+            continue;
+        };
+
+        let mut cursor = name_end;
+        let mut args = Vec::with_capacity(macro_def.arg_count);
+        for _ in 0..macro_def.arg_count {
+            let Some(rest) = math.get(cursor..) else {
+                break;
+            };
+            let Some(body) = rest.strip_prefix('{') else {
+                break;
+            };
+            let Some(close) = body.find('}') else {
+                break;
+            };
+            args.push(&body[..close]);
+            cursor += 1 + close + 1;
+        }
+
+        if args.len() < macro_def.arg_count {
+            // not enough arguments present; leave the command as-is
+            out.push_str(&math[i..name_end]);
+            i = name_end;
+            continue;
+        }
+
+        let mut expansion = macro_def.replacement.clone();
+        for (idx, arg) in args.iter().enumerate() {
+            expansion = expansion.replace(&format!("#{}", idx + 1), arg);
+        }
+        out.push_str(&expansion);
+        i = cursor;
+    }
+
+    out
+}
+
+#[cached(
+    size = 128,
+    key = "(String, bool, u64)",
+    convert = r#"{ (math.clone(), block_display_mode, macro_table_hash(&macros)) }"#
+)]
+pub fn math_render_cached(
+    math: String,
+    block_display_mode: bool,
+    macros: HashMap<String, MathMacro>,
+) -> String {
+    math_render(math, block_display_mode, macros)
+}
+
+pub fn math_render(
+    math: String,
+    block_display_mode: bool,
+    macros: HashMap<String, MathMacro>,
+) -> String {
+    let source_id = if block_display_mode {
+        "display math"
+    } else {
+        "inline math"
+    };
+    match math_try_render(source_id, &math, block_display_mode, &macros) {
+        Ok(mathml) => mathml,
+        Err(_) => encode_text(&math).to_string(),
+    }
+}
+
+/// Attempt to render `math` (after macro expansion) to MathML, returning a
+/// source-spanned [`SourceDiagnostic`] instead of silently falling back to
+/// escaped text on failure. [`math_render`] (used by the HTML rendering
+/// path) discards the diagnostic and falls back; callers running in a
+/// linting/validation mode can call this directly to surface the error to
+/// document authors.
+pub fn math_try_render(
+    source_id: &str,
+    math: &str,
+    block_display_mode: bool,
+    macros: &HashMap<String, MathMacro>,
+) -> Result<String, SourceDiagnostic> {
+    let expanded = expand_macros(math, macros);
     let storage = Storage::new();
-    let parser = Parser::new(&math, &storage);
+    let parser = Parser::new(&expanded, &storage);
     let mut config: RenderConfig = Default::default();
     config.display_mode = if block_display_mode {
         DisplayMode::Block
@@ -22,16 +203,30 @@ pub fn math_render(math: String, block_display_mode: bool) -> String {
     };
     let mut mathml = String::new();
 
-    // NOTE(Rehan): some parsing errors show up in the actual converted text for whatever reason (not raised as an error)
-    // so we manually parse the text for the error strings to avoid presenting that to the user
+    // NOTE(Rehan): some parsing errors show up in the actual converted text
+    // for whatever reason (not raised as an `Err`), so we manually check
+    // the text for the error markers. And since pulldown-latex's error type
+    // doesn't expose a byte span we can rely on without vendoring its
+    // source, we point the diagnostic at the whole macro-expanded
+    // expression rather than guessing at an offset within it -- an honest
+    // whole-span label beats a fabricated precise one.
     match push_mathml(&mut mathml, parser, config) {
-        Ok(()) => {
-            if mathml.contains("parsing error") && mathml.contains("╭─►") {
-                encode_text(&math).to_string()
-            } else {
-                mathml
-            }
+        Ok(()) if mathml.contains("parsing error") && mathml.contains("╭─►") => {
+            Err(SourceDiagnostic::new(
+                source_id.to_string(),
+                expanded.clone(),
+                0..expanded.len(),
+                "LaTeX math failed to render",
+                "pulldown-latex reported a parsing error here",
+            ))
         }
-        Err(_) => encode_text(&math).to_string(),
+        Ok(()) => Ok(mathml),
+        Err(err) => Err(SourceDiagnostic::new(
+            source_id.to_string(),
+            expanded.clone(),
+            0..expanded.len(),
+            format!("LaTeX math failed to render: {:?}", err),
+            "failed to parse this expression",
+        )),
     }
 }
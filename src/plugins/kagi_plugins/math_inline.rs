@@ -1,7 +1,12 @@
+use std::collections::HashMap;
+
 use crate::mdparser::inline::{InlineRule, InlineState};
 use crate::mdparser::preprocess::INLINE_MATH_DOLLAR_REGEX;
 use crate::plugin_config::InlineMathExtensionPlugin;
-use crate::plugins::kagi_plugins::math::{math_render, math_render_cached};
+use crate::plugins::kagi_plugins::diagnostic::SourceDiagnostic;
+use crate::plugins::kagi_plugins::math::{
+    build_macro_table, math_render, math_render_cached, math_try_render, MathMacro,
+};
 use crate::{MarkdownIt, Node, NodeValue, Renderer};
 use html_escape::decode_html_entities;
 
@@ -9,6 +14,11 @@ use html_escape::decode_html_entities;
 pub struct InlineMath {
     pub math: String,
     pub cache: bool,
+    pub macros: HashMap<String, MathMacro>,
+    /// Set when this expression failed to render, for a linting/validation
+    /// mode (see [`diagnostic`](crate::plugins::kagi_plugins::diagnostic));
+    /// rendering itself still falls back to escaped text regardless.
+    pub diagnostic: Option<SourceDiagnostic>,
 }
 
 impl NodeValue for InlineMath {
@@ -18,7 +28,11 @@ impl NodeValue for InlineMath {
         } else {
             math_render
         };
-        fmt.text_raw(&math_render_func(self.math.clone(), false))
+        fmt.text_raw(&math_render_func(
+            self.math.clone(),
+            false,
+            self.macros.clone(),
+        ))
     }
 }
 
@@ -48,10 +62,15 @@ impl InlineRule for MathInlineScanner {
         if let Ok(Some(caps)) = INLINE_MATH_DOLLAR_REGEX.captures(input) {
             let complete_match = &caps[0];
             let math = caps.name("math")?.as_str();
+            let math = decode_html_entities(math).to_string();
+            let macros = build_macro_table(&config.macros);
+            let diagnostic = math_try_render("inline math", &math, false, &macros).err();
             Some((
                 Node::new(InlineMath {
-                    math: decode_html_entities(math).to_string(),
+                    math,
                     cache: config.cache,
+                    macros,
+                    diagnostic,
                 }),
                 complete_match.len(),
             ))
@@ -1,5 +1,9 @@
+pub mod autolink_url;
 pub mod citation;
 pub mod contact_info;
+pub mod diagnostic;
+pub mod diagram;
+pub mod figure;
 pub mod image;
 pub mod inkjet;
 pub mod link;
@@ -33,8 +37,10 @@ pub fn add(md: &mut MarkdownIt) {
     inline::escape::add(md);
     inline::backticks::add(md);
     inline::emphasis::add(md);
+    autolink_url::add(md, crate::plugin_config::AutolinkUrlExtensionPlugin::default());
 
     image::add(md, ImageExtensionPlugin::default());
+    figure::add(md);
     link::add(md, LinkExtensionPlugin::default());
     citation::add(md, CitationExtensionPlugin::default());
     contact_info::add(md);
@@ -52,6 +58,7 @@ pub fn add(md: &mut MarkdownIt) {
         md,
         crate::plugin_config::InlineMathExtensionPlugin::default(),
     );
+    md.add_rule::<diagnostic::DiagnosticsCollectorRule>();
 
     block::code::add(md);
     block::fence::add(md);
@@ -64,12 +71,13 @@ pub fn add(md: &mut MarkdownIt) {
     block::paragraph::add(md);
 }
 
-pub const KAGI_PLUGIN_NAMES: [&str; 23] = [
+pub const KAGI_PLUGIN_NAMES: [&str; 24] = [
     "nl2br",
     "newline",
     "escape",
     "backticks",
     "emphasis",
+    "autolink_url",
     "kagi_image",
     "kagi_link",
     "kagi_contact_info",
@@ -16,8 +16,10 @@
 //! ```
 pub mod autolinks;
 pub mod cmark;
+pub mod diagnostics;
 pub mod extra;
 pub mod footnote;
 pub mod html;
 pub mod kagi_plugins;
+pub mod refs;
 pub mod sourcepos;
@@ -0,0 +1,164 @@
+//! Named cross-reference & anchor subsystem.
+//!
+//! Authors can declare a named anchor anywhere in a document with `{#name}`
+//! and link to it from elsewhere with `[#name]`, giving long documents
+//! stable intra-document links that don't depend on heading text (compare
+//! [`heading_anchors`](crate::plugins::extra::heading_anchors), which slugs
+//! anchors automatically but can't be referenced by a hand-picked name).
+//!
+//! ```rust
+//! let parser = &mut quickmark::MarkdownIt::new();
+//! quickmark::plugins::cmark::add(parser);
+//! quickmark::plugins::refs::add(parser);
+//! let html = parser.parse("{#intro}\n\nSee [#intro] above.").render();
+//! assert!(html.contains(r#"<a id="intro">"#));
+//! assert!(html.contains(r#"<a href="#intro">"#));
+//! ```
+use std::collections::HashMap;
+
+use crate::mdparser::core::{CoreRule, Root};
+use crate::mdparser::extset::RootExt;
+use crate::mdparser::inline::{InlineRule, InlineState};
+use crate::plugins::diagnostics::{Diagnostic, Diagnostics, UNDEFINED_REFERENCE};
+use crate::{MarkdownIt, Node, NodeValue, Renderer};
+
+/// Validate a reference/anchor name: trims leading/trailing whitespace,
+/// rejects an empty name, and rejects any name containing ASCII
+/// punctuation, whitespace, or control codepoints.
+pub fn validate_refname(name: &str) -> Result<String, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("refname must not be empty".to_string());
+    }
+    if let Some(c) = trimmed
+        .chars()
+        .find(|c| c.is_whitespace() || c.is_ascii_punctuation() || c.is_control())
+    {
+        return Err(format!(
+            "refname {:?} contains invalid character {:?}",
+            trimmed, c
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Add the named cross-reference plugin to the parser
+pub fn add(md: &mut MarkdownIt) {
+    md.inline.add_rule::<RefAnchorScanner>();
+    md.inline.add_rule::<RefLinkScanner>();
+    md.add_rule::<RefResolverRule>();
+}
+
+/// An anchor declared with `{#name}`
+#[derive(Debug)]
+pub struct RefAnchor {
+    pub name: String,
+}
+impl NodeValue for RefAnchor {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let mut attrs = node.attrs.clone();
+        attrs.push(("id", self.name.clone()));
+        fmt.open("a", &attrs);
+        fmt.close("a");
+    }
+}
+
+/// A reference to a declared anchor, written as `[#name]`
+#[derive(Debug)]
+pub struct RefLink {
+    pub name: String,
+}
+impl NodeValue for RefLink {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let mut attrs = node.attrs.clone();
+        attrs.push(("href", format!("#{}", self.name)));
+        fmt.open("a", &attrs);
+        fmt.text(&self.name);
+        fmt.close("a");
+    }
+}
+
+/// Duplicate anchor declarations raised by [`RefResolverRule`], stored on
+/// the root node rather than dropped so callers can surface them to
+/// document authors. Undefined references are reported through the
+/// shared [`Diagnostics`](crate::plugins::diagnostics::Diagnostics)
+/// mechanism instead (see [`UNDEFINED_REFERENCE`]), since that's also
+/// where other plugins' dangling-reference diagnostics live.
+#[derive(Debug, Default)]
+pub struct RefDiagnostics(pub Vec<String>);
+impl RootExt for RefDiagnostics {}
+
+struct RefAnchorScanner;
+impl InlineRule for RefAnchorScanner {
+    const MARKER: char = '{';
+
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let input = &state.src[state.pos..state.pos_max];
+        let rest = input.strip_prefix("{#")?;
+        let end = rest.find('}')?;
+        let name = validate_refname(&rest[..end]).ok()?;
+
+        Some((Node::new(RefAnchor { name }), end + 3))
+    }
+}
+
+struct RefLinkScanner;
+impl InlineRule for RefLinkScanner {
+    const MARKER: char = '[';
+
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        let input = &state.src[state.pos..state.pos_max];
+        let rest = input.strip_prefix("[#")?;
+        let end = rest.find(']')?;
+        let name = validate_refname(&rest[..end]).ok()?;
+
+        Some((Node::new(RefLink { name }), end + 3))
+    }
+}
+
+/// A [CoreRule] that builds a map of declared refnames and flags undefined
+/// references and duplicate anchors as diagnostics rather than silently
+/// dropping them.
+struct RefResolverRule;
+impl CoreRule for RefResolverRule {
+    fn run(root: &mut Node, _: &MarkdownIt) {
+        let mut declared: HashMap<String, usize> = HashMap::new();
+        root.walk(|node, _| {
+            if let Some(anchor) = node.cast::<RefAnchor>() {
+                *declared.entry(anchor.name.clone()).or_insert(0) += 1;
+            }
+        });
+
+        let mut diagnostics = Vec::new();
+        for (name, count) in &declared {
+            if *count > 1 {
+                diagnostics.push(format!(
+                    "duplicate anchor {:?} declared {} times",
+                    name, count
+                ));
+            }
+        }
+
+        let mut undefined = Vec::new();
+        root.walk(|node, _| {
+            if let Some(link) = node.cast::<RefLink>() {
+                if !declared.contains_key(&link.name) {
+                    let span = node.srcmap.map(|(start, end)| start..end).unwrap_or(0..0);
+                    let message = format!("reference [#{}] has no matching anchor", link.name);
+                    undefined.push(Diagnostic::new_internal(UNDEFINED_REFERENCE, span, message));
+                }
+            }
+        });
+
+        let data = root.cast_mut::<Root>().unwrap();
+        if !diagnostics.is_empty() {
+            data.ext.insert(RefDiagnostics(diagnostics));
+        }
+        if !undefined.is_empty() {
+            data.ext
+                .get_or_insert_default::<Diagnostics>()
+                .0
+                .extend(undefined);
+        }
+    }
+}